@@ -0,0 +1,86 @@
+use std::fmt;
+use std::io;
+
+/// The ways in which reading a 3MF package can fail.
+///
+#[derive(Debug)]
+pub enum ThreeMfError {
+    /// The underlying reader failed.
+    Io(io::Error),
+
+    /// The package isn't a valid zip archive.
+    Zip(zip::result::ZipError),
+
+    /// The model part isn't well-formed XML.
+    Xml(roxmltree::Error),
+
+    /// The package has no model part.
+    MissingModel,
+
+    /// An attribute is missing or can't be parsed.
+    BadAttribute { element: String, attribute: String, value: String },
+
+    /// A component or build item refers to an object that doesn't exist, or to one of its own
+    /// ancestors.
+    BadReference { object_id: u32 },
+
+    /// A triangle refers to a vertex that doesn't exist.
+    BadIndex { object_id: u32, index: usize }
+}
+
+impl fmt::Display for ThreeMfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ThreeMfError::Io(why) => {
+                write!(f, "i/o error: {}", why)
+            }
+            ThreeMfError::Zip(why) => {
+                write!(f, "invalid package: {}", why)
+            }
+            ThreeMfError::Xml(why) => {
+                write!(f, "invalid model: {}", why)
+            }
+            ThreeMfError::MissingModel => {
+                write!(f, "the package has no 3D model part")
+            }
+            ThreeMfError::BadAttribute { element, attribute, value } => {
+                write!(f, "<{}> has a missing or invalid '{}' attribute: '{}'", element, attribute, value)
+            }
+            ThreeMfError::BadReference { object_id } => {
+                write!(f, "reference to missing (or cyclic) object {}", object_id)
+            }
+            ThreeMfError::BadIndex { object_id, index } => {
+                write!(f, "object {} refers to vertex {}, which doesn't exist", object_id, index)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ThreeMfError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ThreeMfError::Io(why) => Some(why),
+            ThreeMfError::Zip(why) => Some(why),
+            ThreeMfError::Xml(why) => Some(why),
+            _ => None
+        }
+    }
+}
+
+impl From<io::Error> for ThreeMfError {
+    fn from(why: io::Error) -> Self {
+        ThreeMfError::Io(why)
+    }
+}
+
+impl From<zip::result::ZipError> for ThreeMfError {
+    fn from(why: zip::result::ZipError) -> Self {
+        ThreeMfError::Zip(why)
+    }
+}
+
+impl From<roxmltree::Error> for ThreeMfError {
+    fn from(why: roxmltree::Error) -> Self {
+        ThreeMfError::Xml(why)
+    }
+}
@@ -0,0 +1,86 @@
+/// A column-major affine transform, as in glTF (the bottom row is always 0 0 0 1).
+pub type Transform = [[f32; 4]; 4];
+
+pub const IDENTITY: Transform = [[1.0, 0.0, 0.0, 0.0], [0.0, 1.0, 0.0, 0.0], [0.0, 0.0, 1.0, 0.0], [0.0, 0.0, 0.0, 1.0]];
+
+/// A reference from one object (or a build item) to another, placed with a transform.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Component {
+    pub object_id: u32,
+    pub transform: Transform
+}
+
+/// A 3MF object, which holds either a triangle mesh or components (references to other objects).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Object {
+    pub id: u32,
+    pub name: String,
+    pub vertices: Vec<[f32; 3]>,
+    pub triangles: Vec<[usize; 3]>,
+    pub components: Vec<Component>
+}
+
+/// The contents of the root model part of a 3MF package.
+///
+/// Each build item places an object on the build plate. Materials and the other extensions aren't
+/// read.
+///
+/// see: https://github.com/3MFConsortium/spec_core/blob/master/3MF%20Core%20Specification.md
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct ThreeMf {
+    /// The unit of the coordinates (i.e. "millimeter").
+    pub unit: String,
+
+    pub objects: Vec<Object>,
+    pub build: Vec<Component>
+}
+
+impl Default for ThreeMf {
+    fn default() -> Self {
+        Self { unit: "millimeter".to_string(), objects: vec![], build: vec![] }
+    }
+}
+
+/// [returns] the transform that applies `b` and then `a`.
+pub fn compose(a: &Transform, b: &Transform) -> Transform {
+    let mut out = [[0.0; 4]; 4];
+    for (col, out_col) in out.iter_mut().enumerate() {
+        for (row, v) in out_col.iter_mut().enumerate() {
+            *v = (0..4).map(|k| a[k][row] * b[col][k]).sum();
+        }
+    }
+    out
+}
+
+impl ThreeMf {
+    pub fn object(&self, id: u32) -> Option<&Object> {
+        self.objects.iter().find(|o| o.id == id)
+    }
+
+    /// [returns] every mesh placed by the build, as (index into `objects`, world transform) pairs,
+    ///           with components flattened out.
+    ///
+    /// [note]    The reader rejects files with unknown or cyclic references, so this terminates.
+    pub fn instances(&self) -> Vec<(usize, Transform)> {
+        let mut instances = vec![];
+
+        fn visit(model: &ThreeMf, id: u32, transform: Transform, instances: &mut Vec<(usize, Transform)>) {
+            let Some(index) = model.objects.iter().position(|o| o.id == id) else { return };
+            let object = &model.objects[index];
+
+            if !object.triangles.is_empty() {
+                instances.push((index, transform));
+            }
+            for component in &object.components {
+                visit(model, component.object_id, compose(&transform, &component.transform), instances);
+            }
+        }
+
+        for item in &self.build {
+            visit(self, item.object_id, item.transform, &mut instances);
+        }
+
+        instances
+    }
+}
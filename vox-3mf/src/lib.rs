@@ -0,0 +1,35 @@
+pub mod error;
+pub mod fwd;
+
+mod parser;
+mod writer;
+
+pub mod three_mf {
+    use std::fs::File;
+    use std::io::{Cursor, Read, Seek, Write};
+    use std::path::Path;
+
+    pub use crate::error::ThreeMfError;
+
+    use crate::fwd::ThreeMf;
+    use crate::parser;
+    use crate::writer;
+
+    pub fn parse_from_file(path: &Path) -> Result<ThreeMf, ThreeMfError> {
+        parse_from_reader(File::open(path)?)
+    }
+
+    pub fn parse_from_reader<R: Read + Seek>(reader: R) -> Result<ThreeMf, ThreeMfError> {
+        parser::three_mf_from_reader(reader)
+    }
+
+    pub fn parse_from_bytes(bytes: &[u8]) -> Result<ThreeMf, ThreeMfError> {
+        parser::three_mf_from_reader(Cursor::new(bytes))
+    }
+
+    /// Writes `model` as a 3MF package (a zip archive, hence the need to seek).
+    ///
+    pub fn write<W: Write + Seek>(writer: W, model: &ThreeMf) -> Result<(), ThreeMfError> {
+        writer::write_three_mf(writer, model)
+    }
+}
@@ -0,0 +1,175 @@
+use std::collections::HashSet;
+use std::io::{Read, Seek};
+use std::str::FromStr;
+
+use roxmltree::Node;
+
+use crate::error::ThreeMfError;
+use crate::fwd::{Component, Object, ThreeMf, Transform, IDENTITY};
+
+/// Where the model part usually lives, for packages whose relationships don't say.
+pub(crate) const DEFAULT_MODEL_PATH: &str = "3D/3dmodel.model";
+
+/// The relationship type of the root model part.
+pub(crate) const MODEL_RELATIONSHIP: &str = "http://schemas.microsoft.com/3dmanufacturing/2013/01/3dmodel";
+
+fn attribute<T: FromStr>(node: &Node, name: &str) -> Result<T, ThreeMfError> {
+    let value = node.attribute(name).unwrap_or("");
+    value.parse::<T>().map_err(|_| ThreeMfError::BadAttribute {
+        element: node.tag_name().name().to_string(),
+        attribute: name.to_string(),
+        value: value.to_string()
+    })
+}
+
+/// [returns] the transform in the (optional) 'transform' attribute of `node`.
+///
+/// 3MF writes the 12 entries of a 4x3 matrix that multiplies row vectors, so its rows are our
+/// columns.
+fn transform(node: &Node) -> Result<Transform, ThreeMfError> {
+    let Some(value) = node.attribute("transform") else { return Ok(IDENTITY) };
+
+    let bad = || ThreeMfError::BadAttribute {
+        element: node.tag_name().name().to_string(),
+        attribute: "transform".to_string(),
+        value: value.to_string()
+    };
+
+    let m = value.split_whitespace().map(|t| t.parse::<f32>()).collect::<Result<Vec<f32>, _>>().map_err(|_| bad())?;
+    if m.len() != 12 {
+        return Err(bad());
+    }
+
+    Ok([
+        [m[0], m[1], m[2], 0.0],
+        [m[3], m[4], m[5], 0.0],
+        [m[6], m[7], m[8], 0.0],
+        [m[9], m[10], m[11], 1.0]
+    ])
+}
+
+fn children<'a, 'input>(node: Node<'a, 'input>, name: &'a str) -> impl Iterator<Item = Node<'a, 'input>> {
+    node.children().filter(move |n| n.is_element() && n.tag_name().name() == name)
+}
+
+fn parse_object(node: Node) -> Result<Object, ThreeMfError> {
+    let mut object = Object {
+        id: attribute(&node, "id")?,
+        name: node.attribute("name").unwrap_or("").to_string(),
+        ..Object::default()
+    };
+
+    for mesh in children(node, "mesh") {
+        for vertex in children(mesh, "vertices").flat_map(|v| children(v, "vertex")) {
+            object.vertices.push([attribute(&vertex, "x")?, attribute(&vertex, "y")?, attribute(&vertex, "z")?]);
+        }
+
+        for triangle in children(mesh, "triangles").flat_map(|t| children(t, "triangle")) {
+            let tri = [attribute(&triangle, "v1")?, attribute(&triangle, "v2")?, attribute(&triangle, "v3")?];
+
+            if let Some(&index) = tri.iter().find(|&&v| v >= object.vertices.len()) {
+                return Err(ThreeMfError::BadIndex { object_id: object.id, index });
+            }
+            object.triangles.push(tri);
+        }
+    }
+
+    for component in children(node, "components").flat_map(|c| children(c, "component")) {
+        object.components.push(Component { object_id: attribute(&component, "objectid")?, transform: transform(&component)? });
+    }
+
+    Ok(object)
+}
+
+/// Checks that every reference resolves, and that no object (indirectly) contains itself.
+///
+/// [note]    Objects already checked are skipped, so shared components are only walked once.
+fn check_references(model: &ThreeMf) -> Result<(), ThreeMfError> {
+    fn visit(model: &ThreeMf, id: u32, path: &mut Vec<u32>, checked: &mut HashSet<u32>) -> Result<(), ThreeMfError> {
+        if checked.contains(&id) {
+            return Ok(());
+        }
+
+        let object = model.object(id).filter(|_| !path.contains(&id)).ok_or(ThreeMfError::BadReference { object_id: id })?;
+
+        path.push(id);
+        for component in &object.components {
+            visit(model, component.object_id, path, checked)?;
+        }
+        path.pop();
+
+        checked.insert(id);
+        Ok(())
+    }
+
+    let mut checked = HashSet::new();
+    for item in &model.build {
+        visit(model, item.object_id, &mut vec![], &mut checked)?;
+    }
+    for object in &model.objects {
+        visit(model, object.id, &mut vec![], &mut checked)?;
+    }
+
+    Ok(())
+}
+
+/// Parses the XML of a model part.
+pub(crate) fn model_from_xml(xml: &str) -> Result<ThreeMf, ThreeMfError> {
+    let document = roxmltree::Document::parse(xml)?;
+    let root = document.root_element();
+
+    let mut model = ThreeMf {
+        unit: root.attribute("unit").unwrap_or("millimeter").to_string(),
+        objects: vec![],
+        build: vec![]
+    };
+
+    for object in children(root, "resources").flat_map(|r| children(r, "object")) {
+        model.objects.push(parse_object(object)?);
+    }
+
+    for item in children(root, "build").flat_map(|b| children(b, "item")) {
+        model.build.push(Component { object_id: attribute(&item, "objectid")?, transform: transform(&item)? });
+    }
+
+    check_references(&model)?;
+
+    Ok(model)
+}
+
+/// [returns] the path (within the package) of the root model part, from the package relationships.
+fn model_path<R: Read + Seek>(archive: &mut zip::ZipArchive<R>) -> Result<String, ThreeMfError> {
+    let mut rels = String::new();
+    match archive.by_name("_rels/.rels") {
+        Ok(mut file) => file.read_to_string(&mut rels)?,
+        Err(_) => return Ok(DEFAULT_MODEL_PATH.to_string())
+    };
+
+    let document = roxmltree::Document::parse(&rels)?;
+
+    let target = document.descendants()
+        .filter(|n| n.tag_name().name() == "Relationship" && n.attribute("Type") == Some(MODEL_RELATIONSHIP))
+        .find_map(|n| n.attribute("Target"))
+        .unwrap_or(DEFAULT_MODEL_PATH);
+
+    Ok(target.trim_start_matches('/').to_string())
+}
+
+/// A simple reader for 3MF packages, which reads the mesh objects and build items of the root
+/// model part.
+///
+/// see: https://github.com/3MFConsortium/spec_core/blob/master/3MF%20Core%20Specification.md
+///
+pub fn three_mf_from_reader<R: Read + Seek>(reader: R) -> Result<ThreeMf, ThreeMfError> {
+    let mut archive = zip::ZipArchive::new(reader)?;
+    let path = model_path(&mut archive)?;
+
+    let mut xml = String::new();
+    match archive.by_name(&path) {
+        Ok(mut file) => file.read_to_string(&mut xml)?,
+        Err(zip::result::ZipError::FileNotFound) => return Err(ThreeMfError::MissingModel),
+        Err(why) => return Err(ThreeMfError::from(why))
+    };
+
+    model_from_xml(&xml)
+}
@@ -0,0 +1,103 @@
+use std::io::{Seek, Write};
+
+use zip::write::FileOptions;
+
+use crate::error::ThreeMfError;
+use crate::fwd::{ThreeMf, Transform, IDENTITY};
+use crate::parser::{DEFAULT_MODEL_PATH, MODEL_RELATIONSHIP};
+
+const CONTENT_TYPES: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">
+  <Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>
+  <Default Extension="model" ContentType="application/vnd.ms-package.3dmanufacturing-3dmodel+xml"/>
+</Types>
+"#;
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// [returns] the 'transform' attribute for `transform` (with a leading space), or nothing for the
+///           identity.
+fn transform_attribute(transform: &Transform) -> String {
+    if *transform == IDENTITY {
+        return String::new();
+    }
+
+    let m : Vec<String> = transform.iter().flat_map(|col| col[..3].iter().map(|v| v.to_string())).collect();
+    format!(" transform=\"{}\"", m.join(" "))
+}
+
+fn model_xml(model: &ThreeMf) -> String {
+    let mut xml = String::new();
+
+    xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<model unit=\"{}\" xml:lang=\"en-US\" xmlns=\"http://schemas.microsoft.com/3dmanufacturing/core/2015/02\">\n",
+        escape(&model.unit)
+    ));
+    xml.push_str("  <resources>\n");
+
+    for object in &model.objects {
+        xml.push_str(&format!("    <object id=\"{}\" name=\"{}\" type=\"model\">\n", object.id, escape(&object.name)));
+
+        if !object.triangles.is_empty() || object.components.is_empty() {
+            xml.push_str("      <mesh>\n        <vertices>\n");
+            for v in &object.vertices {
+                xml.push_str(&format!("          <vertex x=\"{}\" y=\"{}\" z=\"{}\"/>\n", v[0], v[1], v[2]));
+            }
+            xml.push_str("        </vertices>\n        <triangles>\n");
+            for t in &object.triangles {
+                xml.push_str(&format!("          <triangle v1=\"{}\" v2=\"{}\" v3=\"{}\"/>\n", t[0], t[1], t[2]));
+            }
+            xml.push_str("        </triangles>\n      </mesh>\n");
+        }
+
+        if !object.components.is_empty() {
+            xml.push_str("      <components>\n");
+            for c in &object.components {
+                xml.push_str(&format!("        <component objectid=\"{}\"{}/>\n", c.object_id, transform_attribute(&c.transform)));
+            }
+            xml.push_str("      </components>\n");
+        }
+
+        xml.push_str("    </object>\n");
+    }
+
+    xml.push_str("  </resources>\n  <build>\n");
+    for item in &model.build {
+        xml.push_str(&format!("    <item objectid=\"{}\"{}/>\n", item.object_id, transform_attribute(&item.transform)));
+    }
+    xml.push_str("  </build>\n</model>\n");
+
+    xml
+}
+
+/// A simple writer for 3MF packages, holding a single model part.
+///
+/// [note] The spec only allows an object to hold a mesh *or* components. Objects with both are
+///        written as-is, and objects with neither get an empty mesh.
+///
+pub fn write_three_mf<W: Write + Seek>(writer: W, model: &ThreeMf) -> Result<(), ThreeMfError> {
+    let mut zip = zip::ZipWriter::new(writer);
+    let options = FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+    zip.start_file("[Content_Types].xml", options)?;
+    zip.write_all(CONTENT_TYPES.as_bytes())?;
+
+    zip.start_file("_rels/.rels", options)?;
+    write!(
+        zip,
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\n  \
+         <Relationship Target=\"/{}\" Id=\"rel0\" Type=\"{}\"/>\n\
+         </Relationships>\n",
+        DEFAULT_MODEL_PATH, MODEL_RELATIONSHIP
+    )?;
+
+    zip.start_file(DEFAULT_MODEL_PATH, options)?;
+    zip.write_all(model_xml(model).as_bytes())?;
+
+    zip.finish()?;
+    Ok(())
+}
@@ -0,0 +1,97 @@
+use std::io::Cursor;
+use std::path::Path;
+
+use vox_3mf::fwd::{Component, Object, ThreeMf, IDENTITY};
+use vox_3mf::three_mf;
+use vox_3mf::three_mf::ThreeMfError;
+
+#[test]
+fn parse_3mf() {
+    let model = three_mf::parse_from_file(Path::new("../resources/models/tetra-pair.3mf")).unwrap();
+
+    assert_eq!(model.unit, "millimeter");
+    assert_eq!(model.objects.len(), 2);
+
+    let tetra = model.object(1).unwrap();
+    assert_eq!(tetra.name, "tetra");
+    assert_eq!(tetra.vertices[1], [10.0, 0.0, 0.0]);
+    assert_eq!(tetra.triangles[3], [1, 2, 3]);
+
+    let pair = model.object(2).unwrap();
+    assert!(pair.triangles.is_empty());
+    assert_eq!(pair.components[1].transform[3], [20.0, 0.0, 0.0, 1.0]);
+
+    // The rows of the 3MF matrix are the columns of ours.
+    assert_eq!(model.build[0].transform, [
+        [0.0, 1.0, 0.0, 0.0],
+        [-1.0, 0.0, 0.0, 0.0],
+        [0.0, 0.0, 1.0, 0.0],
+        [5.0, 5.0, 0.0, 1.0]
+    ]);
+
+    // The pair places the tetrahedron twice, and the second build item once more.
+    let instances = model.instances();
+    assert_eq!(instances.len(), 3);
+    assert!(instances.iter().all(|(object, _)| *object == 0));
+
+    // The second copy in the pair is moved along x, which the item's rotation turns into y.
+    assert_eq!(instances[1].1[3], [5.0, 25.0, 0.0, 1.0]);
+}
+
+#[test]
+fn write_3mf_round_trip() {
+    let model = three_mf::parse_from_file(Path::new("../resources/models/tetra-pair.3mf")).unwrap();
+
+    let mut bytes = Cursor::new(vec![]);
+    three_mf::write(&mut bytes, &model).unwrap();
+
+    assert_eq!(three_mf::parse_from_bytes(bytes.get_ref()).unwrap(), model);
+}
+
+#[test]
+fn parse_malformed_3mf() {
+    let write = |model: &ThreeMf| {
+        let mut bytes = Cursor::new(vec![]);
+        three_mf::write(&mut bytes, model).unwrap();
+        three_mf::parse_from_bytes(bytes.get_ref())
+    };
+
+    let tetra = Object {
+        id: 1,
+        vertices: vec![[0.0; 3]; 3],
+        triangles: vec![[0, 1, 2]],
+        ..Object::default()
+    };
+
+    // [Scenario] not a zip archive
+    assert!(matches!(three_mf::parse_from_bytes(b"solid cube\n"), Err(ThreeMfError::Zip(_))));
+
+    // [Scenario] a triangle refers to a vertex that doesn't exist
+    let mut model = ThreeMf { objects: vec![tetra.clone()], ..ThreeMf::default() };
+    model.objects[0].triangles.push([0, 1, 3]);
+    assert!(matches!(write(&model), Err(ThreeMfError::BadIndex { object_id: 1, index: 3 })));
+
+    // [Scenario] a build item refers to an object that doesn't exist
+    let model = ThreeMf {
+        objects: vec![tetra.clone()],
+        build: vec![Component { object_id: 7, transform: IDENTITY }],
+        ..ThreeMf::default()
+    };
+    assert!(matches!(write(&model), Err(ThreeMfError::BadReference { object_id: 7 })));
+
+    // [Scenario] an object contains itself
+    let mut cyclic = tetra.clone();
+    cyclic.components.push(Component { object_id: 1, transform: IDENTITY });
+    let model = ThreeMf { objects: vec![cyclic], ..ThreeMf::default() };
+    assert!(matches!(write(&model), Err(ThreeMfError::BadReference { object_id: 1 })));
+
+    // [Scenario] a deep chain of objects that each contain the next one twice (which would take
+    //            2^40 steps to check if shared objects were walked again)
+    let mut objects = vec![tetra.clone()];
+    for id in 2..=40 {
+        let components = vec![Component { object_id: id - 1, transform: IDENTITY }; 2];
+        objects.push(Object { id, components, ..Object::default() });
+    }
+    let model = ThreeMf { objects, ..ThreeMf::default() };
+    assert_eq!(write(&model).unwrap(), model);
+}
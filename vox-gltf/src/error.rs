@@ -0,0 +1,87 @@
+use std::fmt;
+use std::io;
+
+/// The ways in which loading a glTF file can fail.
+///
+#[derive(Debug)]
+pub enum GltfError {
+    /// A file (the glTF itself, or a sidecar buffer or image) couldn't be read.
+    Io(io::Error),
+
+    /// The JSON or GLB container is malformed or invalid.
+    Gltf(gltf::Error),
+
+    /// A buffer or image refers to something other than a data URI or a local file within the glTF
+    /// file's own directory.
+    UnsupportedUri(String),
+
+    /// A data URI isn't valid base64.
+    BadDataUri(String),
+
+    /// A buffer is shorter than the byte length it declares (or a GLB has no binary chunk).
+    MissingBufferData { buffer: usize },
+
+    /// An image couldn't be decoded.
+    BadImage { image: usize, why: image::ImageError },
+
+    /// A primitive lacks the positions needed to build a mesh.
+    MissingPositions { mesh: usize, primitive: usize },
+
+    /// A node is reached more than once from the scene's roots (i.e. it's its own ancestor), so the
+    /// nodes don't form a forest.
+    BadNodeHierarchy { node: usize }
+}
+
+impl fmt::Display for GltfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GltfError::Io(why) => {
+                write!(f, "i/o error: {}", why)
+            }
+            GltfError::Gltf(why) => {
+                write!(f, "invalid glTF: {}", why)
+            }
+            GltfError::UnsupportedUri(uri) => {
+                write!(f, "only local files and data URIs are supported, got '{}'", uri)
+            }
+            GltfError::BadDataUri(uri) => {
+                write!(f, "malformed data URI '{}'", uri)
+            }
+            GltfError::MissingBufferData { buffer } => {
+                write!(f, "buffer {} holds fewer bytes than it declares", buffer)
+            }
+            GltfError::BadImage { image, why } => {
+                write!(f, "image {} couldn't be decoded: {}", image, why)
+            }
+            GltfError::MissingPositions { mesh, primitive } => {
+                write!(f, "primitive {} of mesh {} has no POSITION attribute", primitive, mesh)
+            }
+            GltfError::BadNodeHierarchy { node } => {
+                write!(f, "node {} appears more than once in the scene's hierarchy", node)
+            }
+        }
+    }
+}
+
+impl std::error::Error for GltfError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            GltfError::Io(why) => Some(why),
+            GltfError::Gltf(why) => Some(why),
+            GltfError::BadImage { why, .. } => Some(why),
+            _ => None
+        }
+    }
+}
+
+impl From<io::Error> for GltfError {
+    fn from(why: io::Error) -> Self {
+        GltfError::Io(why)
+    }
+}
+
+impl From<gltf::Error> for GltfError {
+    fn from(why: gltf::Error) -> Self {
+        GltfError::Gltf(why)
+    }
+}
@@ -0,0 +1,69 @@
+/// The triangles of a single glTF primitive, with their attributes flattened out of the buffers.
+///
+/// Strips and fans are expanded into plain triangle lists, and non-indexed primitives are given
+/// sequential indices, so `indices` always holds 3 entries per triangle.
+///
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Primitive {
+    pub positions: Vec<[f32; 3]>,
+
+    // Either empty or has one entry per position.
+    pub normals: Vec<[f32; 3]>,
+    pub uvs: Vec<[f32; 2]>,
+    pub indices: Vec<u32>,
+
+    // The index into `Scene::materials`, if the primitive has a material.
+    pub material: Option<usize>
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Mesh {
+    pub name: String,
+    pub primitives: Vec<Primitive>
+}
+
+/// The parts of a glTF PBR material that matter to us.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Material {
+    pub name: String,
+
+    /// Linear RGBA, multiplied with the texture (if there is one).
+    pub base_color_factor: [f32; 4],
+
+    /// The index into `Scene::images` of the base-colour texture.
+    pub base_color_texture: Option<usize>
+}
+
+/// A decoded image, as 8-bit RGBA rows from the top.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>
+}
+
+/// A mesh placed in the scene by a node.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Instance {
+    pub node: String,
+
+    /// The index into `Scene::meshes`.
+    pub mesh: usize,
+
+    /// The world-from-mesh transform (the product of the transforms of the node and all of its
+    /// ancestors), column-major as in glTF.
+    pub transform: [[f32; 4]; 4]
+}
+
+/// The meshes, materials and images of a glTF file, along with where the default scene puts each
+/// mesh.
+///
+/// see: https://registry.khronos.org/glTF/specs/2.0/glTF-2.0.html
+///
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Scene {
+    pub meshes: Vec<Mesh>,
+    pub materials: Vec<Material>,
+    pub images: Vec<Image>,
+    pub instances: Vec<Instance>
+}
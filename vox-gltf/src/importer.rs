@@ -0,0 +1,230 @@
+use std::fs;
+use std::path::{Component, Path};
+
+use base64::Engine;
+use gltf::mesh::Mode;
+
+use crate::error::GltfError;
+use crate::fwd::{Image, Instance, Material, Mesh, Primitive, Scene};
+
+/// [returns] `uri` with its %XX escapes decoded.
+fn percent_decode(uri: &str) -> String {
+    let bytes = uri.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3).and_then(|h| u8::from_str_radix(std::str::from_utf8(h).ok()?, 16).ok());
+
+        match (bytes[i], hex) {
+            (b'%', Some(b)) => {
+                out.push(b);
+                i += 3;
+            }
+            (b, _) => {
+                out.push(b);
+                i += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Loads the data that `uri` points to, which must be a data URI or a path relative to `base`.
+fn load_uri(uri: &str, base: Option<&Path>) -> Result<Vec<u8>, GltfError> {
+    if let Some(data) = uri.strip_prefix("data:") {
+        let (_, encoded) = data.split_once(";base64,").ok_or_else(|| GltfError::BadDataUri(uri.to_string()))?;
+        return base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .map_err(|_| GltfError::BadDataUri(uri.to_string()));
+    }
+
+    // Anything with a scheme (http:, https:, file: ...) is refused, as are absolute paths and paths
+    // that climb out through '..', since a file shouldn't be able to pull in data from outside of
+    // its own directory tree.
+    let has_scheme = uri.split_once(':').is_some_and(|(scheme, _)| {
+        !scheme.is_empty() && scheme.chars().all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c))
+    });
+
+    let relative = percent_decode(uri);
+    let path = Path::new(&relative);
+    let is_local = path.components().all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+
+    match base {
+        Some(base) if !has_scheme && is_local => Ok(fs::read(base.join(path))?),
+        _ => Err(GltfError::UnsupportedUri(uri.to_string()))
+    }
+}
+
+fn load_buffers(gltf: &gltf::Gltf, base: Option<&Path>) -> Result<Vec<Vec<u8>>, GltfError> {
+    let mut blob = gltf.blob.clone();
+
+    gltf.buffers().map(|buffer| {
+        let data = match buffer.source() {
+            gltf::buffer::Source::Bin => blob.take().ok_or(GltfError::MissingBufferData { buffer: buffer.index() })?,
+            gltf::buffer::Source::Uri(uri) => load_uri(uri, base)?
+        };
+
+        if data.len() < buffer.length() {
+            return Err(GltfError::MissingBufferData { buffer: buffer.index() });
+        }
+
+        Ok(data)
+    }).collect()
+}
+
+fn load_images(gltf: &gltf::Gltf, buffers: &[Vec<u8>], base: Option<&Path>) -> Result<Vec<Image>, GltfError> {
+    gltf.images().map(|image| {
+        let encoded = match image.source() {
+            gltf::image::Source::View { view, .. } => {
+                let buffer = view.buffer().index();
+                let end = view.offset().checked_add(view.length());
+
+                end.and_then(|end| buffers[buffer].get(view.offset()..end))
+                    .ok_or(GltfError::MissingBufferData { buffer })?
+                    .to_vec()
+            }
+            gltf::image::Source::Uri { uri, .. } => load_uri(uri, base)?
+        };
+
+        let decoded = image::load_from_memory(&encoded)
+            .map_err(|why| GltfError::BadImage { image: image.index(), why })?
+            .into_rgba8();
+
+        Ok(Image { width: decoded.width(), height: decoded.height(), pixels: decoded.into_raw() })
+    }).collect()
+}
+
+/// [returns] `indices` (in the given mode) as a plain list of triangles.
+fn triangle_list(mode: Mode, indices: Vec<u32>) -> Vec<u32> {
+    match mode {
+        Mode::Triangles => indices,
+        Mode::TriangleStrip => (2..indices.len()).flat_map(|i| {
+            // Every other triangle of a strip is wound the other way around.
+            if i % 2 == 0 {
+                [indices[i - 2], indices[i - 1], indices[i]]
+            } else {
+                [indices[i - 1], indices[i - 2], indices[i]]
+            }
+        }).collect(),
+        Mode::TriangleFan => (2..indices.len()).flat_map(|i| [indices[0], indices[i - 1], indices[i]]).collect(),
+
+        // Points and lines have no surface to render.
+        _ => vec![]
+    }
+}
+
+fn load_meshes(gltf: &gltf::Gltf, buffers: &[Vec<u8>]) -> Result<Vec<Mesh>, GltfError> {
+    gltf.meshes().map(|mesh| {
+        let primitives = mesh.primitives().map(|primitive| {
+            let reader = primitive.reader(|buffer| buffers.get(buffer.index()).map(|b| &b[..]));
+
+            let positions : Vec<[f32; 3]> = reader.read_positions()
+                .ok_or(GltfError::MissingPositions { mesh: mesh.index(), primitive: primitive.index() })?
+                .collect();
+
+            let indices = match reader.read_indices() {
+                Some(indices) => indices.into_u32().collect(),
+                None => (0..positions.len() as u32).collect()
+            };
+
+            // Attributes that don't cover every vertex can only come from a broken file, so they're
+            // dropped (rather than leaving corners that point past their ends).
+            let normals : Vec<[f32; 3]> = reader.read_normals().map_or(vec![], |n| n.collect());
+            let uvs : Vec<[f32; 2]> = reader.read_tex_coords(0).map_or(vec![], |uv| uv.into_f32().collect());
+
+            Ok(Primitive {
+                normals: if normals.len() == positions.len() { normals } else { vec![] },
+                uvs: if uvs.len() == positions.len() { uvs } else { vec![] },
+                indices: triangle_list(primitive.mode(), indices),
+                material: primitive.material().index(),
+                positions
+            })
+        }).collect::<Result<_, GltfError>>()?;
+
+        Ok(Mesh { name: mesh.name().unwrap_or("").to_string(), primitives })
+    }).collect()
+}
+
+fn load_materials(gltf: &gltf::Gltf) -> Vec<Material> {
+    gltf.materials().map(|material| {
+        let pbr = material.pbr_metallic_roughness();
+
+        Material {
+            name: material.name().unwrap_or("").to_string(),
+            base_color_factor: pbr.base_color_factor(),
+            base_color_texture: pbr.base_color_texture().map(|info| info.texture().source().index())
+        }
+    }).collect()
+}
+
+fn mul(a: &[[f32; 4]; 4], b: &[[f32; 4]; 4]) -> [[f32; 4]; 4] {
+    let mut out = [[0.0; 4]; 4];
+    for (col, out_col) in out.iter_mut().enumerate() {
+        for (row, v) in out_col.iter_mut().enumerate() {
+            *v = (0..4).map(|k| a[k][row] * b[col][k]).sum();
+        }
+    }
+    out
+}
+
+/// [returns] every mesh instance below the root nodes of `scene`, in depth-first order.
+///
+/// [note]    glTF nodes must form a forest, so a node reached twice (i.e. one that is its own
+///           ancestor) is refused rather than walked forever.
+fn collect_instances(gltf: &gltf::Gltf, scene: gltf::Scene) -> Result<Vec<Instance>, GltfError> {
+    let mut instances = vec![];
+    let mut visited = vec![false; gltf.nodes().len()];
+
+    // An explicit stack, so deep hierarchies can't overflow the call stack either.
+    let mut stack : Vec<(gltf::Node, [[f32; 4]; 4])> = scene.nodes().map(|node| (node, IDENTITY)).collect();
+    stack.reverse();
+
+    while let Some((node, parent)) = stack.pop() {
+        if std::mem::replace(&mut visited[node.index()], true) {
+            return Err(GltfError::BadNodeHierarchy { node: node.index() });
+        }
+
+        let transform = mul(&parent, &node.transform().matrix());
+
+        if let Some(mesh) = node.mesh() {
+            instances.push(Instance {
+                node: node.name().unwrap_or("").to_string(),
+                mesh: mesh.index(),
+                transform
+            });
+        }
+
+        let children : Vec<gltf::Node> = node.children().collect();
+        stack.extend(children.into_iter().rev().map(|child| (child, transform)));
+    }
+
+    Ok(instances)
+}
+
+const IDENTITY: [[f32; 4]; 4] = [[1.0, 0.0, 0.0, 0.0], [0.0, 1.0, 0.0, 0.0], [0.0, 0.0, 1.0, 0.0], [0.0, 0.0, 0.0, 1.0]];
+
+/// Loads a .gltf (with embedded or sidecar buffers) or a .glb.
+///
+/// `base` is the directory that relative URIs are resolved against. Without one, only data URIs
+/// and the GLB binary chunk can be used.
+///
+/// [note] Instances come from the default scene, or the first scene if there is no default. A file
+///        without any scenes places each mesh once, untransformed.
+///
+pub fn scene_from_slice(bytes: &[u8], base: Option<&Path>) -> Result<Scene, GltfError> {
+    let gltf = gltf::Gltf::from_slice(bytes)?;
+
+    let buffers = load_buffers(&gltf, base)?;
+    let images = load_images(&gltf, &buffers, base)?;
+    let meshes = load_meshes(&gltf, &buffers)?;
+    let materials = load_materials(&gltf);
+
+    let instances = match gltf.default_scene().or_else(|| gltf.scenes().next()) {
+        Some(scene) => collect_instances(&gltf, scene)?,
+        None => (0..meshes.len()).map(|mesh| Instance { node: String::new(), mesh, transform: IDENTITY }).collect()
+    };
+
+    Ok(Scene { meshes, materials, images, instances })
+}
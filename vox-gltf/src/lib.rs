@@ -0,0 +1,27 @@
+pub mod error;
+pub mod fwd;
+
+mod importer;
+
+pub mod gltf {
+    use std::fs;
+    use std::path::Path;
+
+    pub use crate::error::GltfError;
+
+    use crate::fwd::Scene;
+    use crate::importer;
+
+    /// Sidecar buffers and images are looked up relative to the file's directory.
+    ///
+    pub fn parse_from_file(path: &Path) -> Result<Scene, GltfError> {
+        let bytes = fs::read(path)?;
+        importer::scene_from_slice(&bytes, Some(path.parent().unwrap_or(Path::new("."))))
+    }
+
+    /// Parses a self-contained .gltf (with data URIs) or .glb that is already in memory.
+    ///
+    pub fn parse_from_bytes(bytes: &[u8]) -> Result<Scene, GltfError> {
+        importer::scene_from_slice(bytes, None)
+    }
+}
@@ -0,0 +1,110 @@
+use std::path::Path;
+
+use vox_gltf::gltf;
+use vox_gltf::gltf::GltfError;
+
+const MODELS: &str = "../resources/models/gltf";
+
+fn assert_near(a: [[f32; 4]; 4], b: [[f32; 4]; 4]) {
+    for (col_a, col_b) in a.iter().zip(&b) {
+        for (x, y) in col_a.iter().zip(col_b) {
+            assert!((x - y).abs() < 1e-5, "{:?} != {:?}", a, b);
+        }
+    }
+}
+
+#[test]
+fn parse_gltf() {
+    // The same scene, stored three ways.
+    for name in ["quad-sidecar.gltf", "quad-embedded.gltf", "quad.glb"] {
+        let scene = gltf::parse_from_file(&Path::new(MODELS).join(name)).unwrap();
+
+        assert_eq!(scene.meshes.len(), 1, "{}", name);
+        assert_eq!(scene.meshes[0].name, "quad");
+
+        let primitive = &scene.meshes[0].primitives[0];
+        assert_eq!(primitive.positions.len(), 4);
+        assert_eq!(primitive.normals.len(), 4);
+        assert_eq!(primitive.uvs[2], [1.0, 0.0]);
+        assert_eq!(primitive.indices, vec![0, 1, 2, 0, 2, 3]);
+        assert_eq!(primitive.material, Some(0));
+
+        assert_eq!(scene.materials[0].base_color_factor, [1.0, 0.5, 0.25, 1.0]);
+        assert_eq!(scene.materials[0].base_color_texture, Some(0));
+
+        assert_eq!((scene.images[0].width, scene.images[0].height), (2, 2));
+        assert_eq!(scene.images[0].pixels[4..8], [0, 255, 0, 255]);
+
+        // The child inherits the root's translation and scale, on top of its own rotation.
+        let names : Vec<&str> = scene.instances.iter().map(|i| i.node.as_str()).collect();
+        assert_eq!(names, vec!["child", "stretched"]);
+
+        assert_near(scene.instances[0].transform, [
+            [0.0, 2.0, 0.0, 0.0],
+            [-2.0, 0.0, 0.0, 0.0],
+            [0.0, 0.0, 2.0, 0.0],
+            [1.0, 2.0, 3.0, 1.0]
+        ]);
+    }
+
+    // [Scenario] the normals stop short of the positions (the accessors are listed positions,
+    //            normals, then texture coordinates), so they're dropped
+    let embedded = std::fs::read_to_string(Path::new(MODELS).join("quad-embedded.gltf")).unwrap();
+    let short = embedded.replacen("\"count\": 4", "\"count\": 3", 2).replacen("\"count\": 3", "\"count\": 4", 1);
+    let primitive = &gltf::parse_from_bytes(short.as_bytes()).unwrap().meshes[0].primitives[0];
+    assert_eq!(primitive.positions.len(), 4);
+    assert!(primitive.normals.is_empty());
+    assert_eq!(primitive.uvs.len(), 4);
+}
+
+#[test]
+fn parse_gltf_local_only() {
+    let embedded = std::fs::read(Path::new(MODELS).join("quad-embedded.gltf")).unwrap();
+    let sidecar = std::fs::read(Path::new(MODELS).join("quad-sidecar.gltf")).unwrap();
+
+    // [Scenario] everything is embedded, so no directory is needed
+    assert!(gltf::parse_from_bytes(&embedded).is_ok());
+
+    // [Scenario] sidecar files can't be found without a directory
+    assert!(matches!(gltf::parse_from_bytes(&sidecar), Err(GltfError::UnsupportedUri(uri)) if uri == "quad.bin"));
+
+    // [Scenario] remote buffers are refused
+    let remote = String::from_utf8(sidecar).unwrap().replace("quad.bin", "https://example.com/quad.bin");
+    let result = gltf::parse_from_bytes(remote.as_bytes());
+    assert!(matches!(result, Err(GltfError::UnsupportedUri(uri)) if uri.starts_with("https:")));
+
+    // [Scenario] paths can't climb out of the file's directory, even when percent-encoded
+    let outside = std::env::temp_dir().join(format!("vox-gltf-escape-{}", std::process::id()));
+    std::fs::create_dir_all(outside.join("inner")).unwrap();
+    std::fs::copy(Path::new(MODELS).join("quad.bin"), outside.join("quad.bin")).unwrap();
+    std::fs::copy(Path::new(MODELS).join("checker.png"), outside.join("checker.png")).unwrap();
+
+    let sidecar = std::fs::read_to_string(Path::new(MODELS).join("quad-sidecar.gltf")).unwrap();
+    for escape in ["../quad.bin", "%2E%2E/quad.bin", "./../quad.bin"] {
+        std::fs::write(outside.join("inner/escape.gltf"), sidecar.replace("\"quad.bin\"", &format!("\"{}\"", escape))).unwrap();
+        let result = gltf::parse_from_file(&outside.join("inner/escape.gltf"));
+        assert!(matches!(result, Err(GltfError::UnsupportedUri(ref uri)) if uri == escape), "{}", escape);
+    }
+
+    // ...whereas the same file next to its buffer loads
+    std::fs::write(outside.join("escape.gltf"), &sidecar).unwrap();
+    assert!(gltf::parse_from_file(&outside.join("escape.gltf")).is_ok());
+    std::fs::remove_dir_all(&outside).unwrap();
+
+    // [Scenario] an image's buffer view runs past the end of its buffer
+    let embedded = String::from_utf8(embedded).unwrap();
+    let image_uri = &embedded[embedded.find("\"uri\": \"data:image/png").unwrap()..];
+    let image_uri = &image_uri[..image_uri.find('\n').unwrap()];
+    let overrun = embedded
+        .replace(image_uri, "\"bufferView\": 4, \"mimeType\": \"image/png\"")
+        .replace("\"byteLength\": 12\n    }", "\"byteLength\": 12\n    },\n    { \"buffer\": 0, \"byteOffset\": 128, \"byteLength\": 4000 }");
+    let result = gltf::parse_from_bytes(overrun.as_bytes());
+    assert!(matches!(result, Err(GltfError::MissingBufferData { buffer: 0 })), "{:?}", result.err());
+
+    // [Scenario] nodes that are each other's children
+    let cyclic = br#"{"asset":{"version":"2.0"},"scene":0,"scenes":[{"nodes":[0]}],"nodes":[{"children":[1]},{"children":[0]}]}"#;
+    assert!(matches!(gltf::parse_from_bytes(cyclic), Err(GltfError::BadNodeHierarchy { node: 0 })));
+
+    // [Scenario] not glTF at all
+    assert!(matches!(gltf::parse_from_bytes(b"solid cube\n"), Err(GltfError::Gltf(_))));
+}
@@ -5,6 +5,7 @@ use image::codecs::png::CompressionType::Default;
 use vox_fwd::{Vec3, Pt3};
 use crate::geometry::{IntersectionType, Plane, Ray, Triangle};
 
+#[derive(Copy, Clone)]
 pub struct BoundingSphere {
     pub center: Pt3,
     pub radius: f32
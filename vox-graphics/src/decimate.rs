@@ -0,0 +1,311 @@
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashSet};
+
+use nalgebra as na;
+
+use vox_fwd::Pt3;
+
+use crate::halfedge::HalfEdgeMesh;
+use crate::model::{Mesh, VertexInfo};
+
+/// The sum of squared distances to a set of planes, as a symmetric 4x4 matrix.
+///
+/// see: Garland & Heckbert, "Surface Simplification Using Quadric Error Metrics" (1997)
+///
+type Quadric = na::Matrix4<f64>;
+
+/// How much more a boundary edge's plane counts than a face's, so that boundaries keep their shape.
+const BOUNDARY_WEIGHT: f64 = 1000.0;
+
+/// What `Mesh::decimate` changed.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct DecimationReport {
+    pub faces_removed: usize,
+    pub vertices_removed: usize,
+
+    /// The largest error of any collapse, as a distance (the root of the quadric error).
+    pub max_error: f32
+}
+
+/// [returns] the quadric of the plane through `p` with unit normal `n`, scaled by `weight`.
+fn plane_quadric(p: &Pt3, n: &na::Vector3<f32>, weight: f64) -> Quadric {
+    let n = n.cast::<f64>();
+    let plane = na::Vector4::new(n.x, n.y, n.z, -n.dot(&p.coords.cast::<f64>()));
+    plane * plane.transpose() * weight
+}
+
+fn quadric_error(q: &Quadric, p: &Pt3) -> f64 {
+    let p = p.coords.cast::<f64>().push(1.0);
+    (p.transpose() * q * p)[0].max(0.0)
+}
+
+/// [returns] the point that minimises the error of `q`, falling back on the best of `a`, `b` and
+///           their midpoint when there isn't a single one (i.e. for flat or straight regions).
+fn collapse_target(q: &Quadric, a: &Pt3, b: &Pt3) -> (Pt3, f64) {
+    let system = q.fixed_slice::<3, 3>(0, 0).into_owned();
+
+    // Relative to the size of the entries, so that the test doesn't depend on the model's scale.
+    if system.determinant().abs() > 1e-10 * system.trace().powi(3) {
+        if let Some(inverse) = system.try_inverse() {
+            let p = Pt3::from((inverse * -q.fixed_slice::<3, 1>(0, 3)).cast::<f32>());
+            return (p, quadric_error(q, &p));
+        }
+    }
+
+    [*a, *b, na::center(a, b)].into_iter()
+        .map(|p| (p, quadric_error(q, &p)))
+        .min_by(|x, y| x.1.total_cmp(&y.1))
+        .unwrap()
+}
+
+/// A candidate edge collapse, which is stale if either vertex has changed since it was queued.
+struct Collapse {
+    cost: f64,
+    edge: [usize; 2],
+    stamps: [usize; 2],
+    target: Pt3
+}
+
+impl PartialEq for Collapse {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Collapse {}
+
+impl PartialOrd for Collapse {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Collapse {
+    // Reversed, so that the cheapest collapse is at the top of the heap.
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.cost.total_cmp(&self.cost).then_with(|| other.edge.cmp(&self.edge))
+    }
+}
+
+/// The state of a decimation in progress.
+struct Decimator<'a> {
+    mesh: &'a mut Mesh,
+    quadrics: Vec<Quadric>,
+    boundary: Vec<bool>,
+    stamps: Vec<usize>,
+    face_alive: Vec<bool>,
+    vertex_alive: Vec<bool>,
+    heap: BinaryHeap<Collapse>
+}
+
+impl Decimator<'_> {
+    fn position(&self, v: usize) -> Pt3 {
+        self.mesh.vertices[v].vtx.0
+    }
+
+    fn live_faces(&self, v: usize) -> impl Iterator<Item = usize> + '_ {
+        self.mesh.vertices[v].faces.iter().copied().filter(|&f| self.face_alive[f])
+    }
+
+    fn neighbours(&self, v: usize) -> HashSet<usize> {
+        self.live_faces(v).flat_map(|f| self.mesh.faces[f].vertices).filter(|&u| u != v).collect()
+    }
+
+    fn queue(&mut self, a: usize, b: usize) {
+        let q = self.quadrics[a] + self.quadrics[b];
+        let (target, cost) = collapse_target(&q, &self.position(a), &self.position(b));
+
+        self.heap.push(Collapse { cost, edge: [a, b], stamps: [self.stamps[a], self.stamps[b]], target });
+    }
+
+    /// [returns] true if collapsing `b` into `a` (at `target`) keeps the surface a manifold without
+    ///           folding any faces over.
+    fn can_collapse(&self, a: usize, b: usize, target: &Pt3) -> bool {
+        let shared : Vec<usize> = self.live_faces(a).filter(|&f| self.mesh.faces[f].vertices.contains(&b)).collect();
+        if shared.is_empty() {
+            return false;
+        }
+
+        // An inner edge between two boundary vertices would pinch the surface together.
+        if self.boundary[a] && self.boundary[b] && shared.len() != 1 {
+            return false;
+        }
+
+        // The link condition: the only vertices next to both are those across the shared faces.
+        if self.neighbours(a).intersection(&self.neighbours(b)).count() != shared.len() {
+            return false;
+        }
+
+        // Something has to be left over (i.e. a lone triangle can't be collapsed into nothing).
+        let mut kept = [a, b].into_iter().flat_map(|v| self.live_faces(v)).filter(|f| !shared.contains(f)).peekable();
+        if kept.peek().is_none() {
+            return false;
+        }
+
+        kept.all(|f| {
+            let vertices = self.mesh.faces[f].vertices;
+            let before = vertices.map(|v| self.position(v));
+            let after = vertices.map(|v| if v == a || v == b { *target } else { self.position(v) });
+
+            let normal = |[p0, p1, p2]: [Pt3; 3]| (p1 - p0).cross(&(p2 - p0));
+            normal(before).dot(&normal(after)) > 0.0
+        })
+    }
+
+    /// Collapses `b` into `a`, moving `a` to `target`.
+    fn collapse(&mut self, a: usize, b: usize, target: Pt3) -> usize {
+        let mut removed = 0;
+
+        for f in self.mesh.vertices[b].faces.clone() {
+            if !self.face_alive[f] {
+                continue;
+            }
+
+            let face = &mut self.mesh.faces[f];
+            if face.vertices.contains(&a) {
+                self.face_alive[f] = false;
+                removed += 1;
+            } else {
+                face.vertices = face.vertices.map(|v| if v == b { a } else { v });
+                self.mesh.vertices[a].faces.push(f);
+            }
+        }
+
+        self.mesh.vertices[a].vtx.0 = target;
+        self.quadrics[a] = self.quadrics[a] + self.quadrics[b];
+        self.boundary[a] |= self.boundary[b];
+        self.stamps[a] += 1;
+        self.vertex_alive[b] = false;
+
+        for v in self.neighbours(a) {
+            self.queue(a, v);
+        }
+
+        removed
+    }
+}
+
+impl Mesh {
+    /// Simplifies the mesh by collapsing edges, cheapest first, until it has at most `target_faces`
+    /// faces or the next collapse would move the surface by more than `max_error` (roughly, as a
+    /// distance from the original faces).
+    ///
+    /// Each collapse merges an edge into a single vertex, placed to stay as close as possible to the
+    /// planes of the original faces around it (its quadric error). Boundary edges carry the planes
+    /// at right angles to their faces as well, so holes and open edges keep their outline.
+    /// Collapses that would fold a face over or make the surface non-manifold are skipped.
+    ///
+    /// [note] The vertices that remain keep their colour, and the corners of the faces keep their
+    ///        normals and uvs, so normals worked out from the faces are best recomputed. Vertices
+    ///        that end up unused are removed.
+    ///
+    pub fn decimate(&mut self, target_faces: usize, max_error: f32) -> DecimationReport {
+        let half_edges = HalfEdgeMesh::from_mesh(self);
+        let faces_before = self.faces.len();
+        let vertices_before = self.vertices.len();
+
+        let mut quadrics = vec![Quadric::zeros(); self.vertices.len()];
+        let mut boundary = vec![false; self.vertices.len()];
+
+        for face in &self.faces {
+            let [p0, p1, p2] = face.vertices.map(|v| self.vertices[v].vtx.0);
+            let normal = (p1 - p0).cross(&(p2 - p0));
+            let Some(n) = normal.try_normalize(0.0) else { continue };
+
+            // Weighted by area, so that slivers don't pull the vertices around.
+            let q = plane_quadric(&p0, &n, normal.norm() as f64 / 2.0);
+            for v in face.vertices {
+                quadrics[v] += q;
+            }
+        }
+
+        for (h, half_edge) in half_edges.half_edges.iter().enumerate() {
+            if !half_edges.is_boundary_edge(h) {
+                continue;
+            }
+
+            let (a, b) = (half_edge.origin, half_edges.destination(h));
+            let [p0, p1, p2] = half_edges.face_vertices(half_edge.face).map(|v| self.vertices[v].vtx.0);
+            let edge = self.vertices[b].vtx.0 - self.vertices[a].vtx.0;
+
+            let Some(n) = edge.cross(&(p1 - p0).cross(&(p2 - p0))).try_normalize(0.0) else { continue };
+            let q = plane_quadric(&self.vertices[a].vtx.0, &n, BOUNDARY_WEIGHT * edge.norm_squared() as f64);
+
+            quadrics[a] += q;
+            quadrics[b] += q;
+            boundary[a] = true;
+            boundary[b] = true;
+        }
+
+        let mut decimator = Decimator {
+            quadrics,
+            boundary,
+            stamps: vec![0; self.vertices.len()],
+            face_alive: vec![true; self.faces.len()],
+            vertex_alive: vec![true; self.vertices.len()],
+            heap: BinaryHeap::new(),
+            mesh: self
+        };
+
+        for h in half_edges.edges() {
+            let (a, b) = (half_edges.half_edges[h].origin, half_edges.destination(h));
+            if a != b {
+                decimator.queue(a.min(b), a.max(b));
+            }
+        }
+
+        let mut report = DecimationReport::default();
+        let mut faces = faces_before;
+        let max_cost = max_error as f64 * max_error as f64;
+
+        while faces > target_faces {
+            let Some(Collapse { cost, edge: [a, b], stamps, target }) = decimator.heap.pop() else { break };
+
+            let stale = !decimator.vertex_alive[a] || !decimator.vertex_alive[b]
+                || stamps != [decimator.stamps[a], decimator.stamps[b]];
+            if stale {
+                continue;
+            }
+
+            if cost > max_cost {
+                break;
+            }
+
+            if decimator.can_collapse(a, b, &target) {
+                faces -= decimator.collapse(a, b, target);
+                report.max_error = report.max_error.max(cost.sqrt() as f32);
+            }
+        }
+
+        // Compact what's left.
+        let Decimator { face_alive, vertex_alive, .. } = decimator;
+
+        let mut face_i = 0;
+        self.faces.retain(|_| {
+            face_i += 1;
+            face_alive[face_i - 1]
+        });
+
+        let mut used = vec![false; self.vertices.len()];
+        self.faces.iter().flat_map(|f| f.vertices).for_each(|v| used[v] = true);
+
+        let mut remap = vec![usize::MAX; self.vertices.len()];
+        let mut vertices : Vec<VertexInfo> = vec![];
+        for (i, vertex) in self.vertices.iter().enumerate() {
+            if vertex_alive[i] && used[i] {
+                remap[i] = vertices.len();
+                vertices.push(VertexInfo { vtx: vertex.vtx, faces: vec![], color: vertex.color });
+            }
+        }
+
+        for face in &mut self.faces {
+            face.vertices = face.vertices.map(|v| remap[v]);
+        }
+        self.vertices = vertices;
+        self.relink_faces();
+
+        report.faces_removed = faces_before - self.faces.len();
+        report.vertices_removed = vertices_before - self.vertices.len();
+        report
+    }
+}
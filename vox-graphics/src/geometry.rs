@@ -5,6 +5,26 @@ extern crate nalgebra as na;
 #[derive(Default, Copy, Clone, Debug, PartialEq)]
 pub struct Triangle<PointType>(pub [PointType; 3]);
 
+impl Triangle<Pt3> {
+    /// [returns] the barycentric coordinates of `p` (projected onto the plane of the triangle).
+    pub fn barycentric(&self, p: &Pt3) -> [f32; 3] {
+        let [a, b, c] = self.0;
+        let (v0, v1, v2) = (b - a, c - a, p - a);
+
+        let (d00, d01, d11) = (v0.dot(&v0), v0.dot(&v1), v1.dot(&v1));
+        let (d20, d21) = (v2.dot(&v0), v2.dot(&v1));
+        let denom = d00 * d11 - d01 * d01;
+
+        if denom == 0.0 {
+            return [1.0, 0.0, 0.0];
+        }
+
+        let v = (d11 * d20 - d01 * d21) / denom;
+        let w = (d00 * d21 - d01 * d20) / denom;
+        [1.0 - v - w, v, w]
+    }
+}
+
 pub struct Plane {
     pub n: Vec3,
     pub d: f32
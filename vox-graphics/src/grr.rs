@@ -1,7 +1,6 @@
 use std::iter::zip;
 
 use std::mem;
-use std::ops::Deref;
 
 use rayon::prelude::*;
 
@@ -10,12 +9,14 @@ use nalgebra as na;
 use vox_fwd::{Pt3, Px2};
 
 use crate::raster;
-use crate::raster::linspace_sample;
+use crate::raster::{linspace_sample, Fragment, Varying};
 use crate::surface::Surface;
 use crate::camera::CameraInfo;
 use crate::clipping::{BoundingSphere, clip_triangle, ClippedTriangle, ClipType, get_clip_type, get_clipping_planes};
 use crate::geometry::Triangle;
 use crate::model::Model;
+use crate::scene::Scene;
+use crate::shader::{DirectionalLight, FragmentShader, LitShader, MatcapShader, Uniforms, VertexShader};
 
 pub fn line_between(p1: raster::Pixel, p2: raster::Pixel) -> Vec<raster::Pixel> {
     return if (p2.y - p1.y).abs() < (p2.x - p1.x).abs() {
@@ -384,22 +385,154 @@ pub fn is_back_facing(tri: &[Pt3; 3], eye_ray: &na::Vector3<f32>) -> bool {
     normal.dot(&(eye_ray - tri[0].coords)) < 0.0
 }
 
+/// A view-space triangle of a face, along with the varyings at its corners.
+#[derive(Clone)]
+struct ShadedTriangle<V> {
+    tri: Triangle<Pt3>,
+    face: usize,
+    corners: [V; 3]
+}
+
+impl<V: Varying> ShadedTriangle<V> {
+    /// [returns] `part` (a piece of this triangle left by clipping), with its corners interpolated
+    ///           from this triangle's.
+    fn piece(&self, part: Triangle<Pt3>) -> Self {
+        let corners = part.0.map(|p| V::blend(&self.corners, &self.tri.barycentric(&p)));
+        ShadedTriangle { tri: part, face: self.face, corners }
+    }
+}
+
+/// Renders the triangle with the clip-space corners `clip`, interpolating `varyings` across it
+/// perspective-correctly (see `raster::rasterize`) and passing each fragment to `shade` for the
+/// colour of its pixel. Fragments that `shade` returns None for are discarded.
+///
+/// [note] The z-buffer holds 1/w, so this shouldn't be mixed with `render_tri` on one surface.
+pub fn render_tri_interpolated<V: Varying>(
+    surface: &mut Surface,
+    clip: &[na::Vector4<f32>; 3],
+    varyings: &[V; 3],
+    shade: impl Fn(&Fragment<V>) -> Option<[u8; 3]>
+) {
+    raster::rasterize(surface.shape, clip, varyings, |fragment| {
+        if fragment.depth > surface.get_z(fragment.x, fragment.y) {
+            if let Some(col) = shade(&fragment) {
+                surface.set_pixel(fragment.x, fragment.y, &col);
+                surface.set_z(fragment.x, fragment.y, fragment.depth);
+            }
+        }
+    });
+}
+
+/// The clip-space corners of a triangle, and the varyings there.
+pub type ClipTriangle<V> = ([na::Vector4<f32>; 3], [V; 3]);
+
+/// How many pixels across (and down) the tiles of `render_tris_tiled` are.
+pub const TILE_SIZE: usize = 64;
+
+/// Renders each of `triangles` in turn, passing its index and each fragment to `shade` for the
+/// colour of the pixel.
+///
+/// The triangles are binned by the tiles of the surface they overlap, and the tiles drawn in
+/// parallel, each into its own copy of the pixels and z-buffer. Each tile draws its triangles in
+/// the order they're given, so the result is the same as calling `render_tri_interpolated` on
+/// each of them in turn.
+///
+pub fn render_tris_tiled<V: Varying + Send + Sync>(
+    surface: &mut Surface,
+    triangles: &[ClipTriangle<V>],
+    shade: impl Fn(usize, &Fragment<V>) -> Option<[u8; 3]> + Sync
+) {
+    let shape = surface.shape;
+    let mut tiles = surface.tiles(TILE_SIZE);
+    let across = shape.0.div_ceil(TILE_SIZE);
+
+    let bounds : Vec<Option<raster::Region>> = triangles.par_iter().map(|(clip, _)| raster::bounds(shape, clip)).collect();
+
+    let mut bins : Vec<Vec<usize>> = vec![vec![]; tiles.len()];
+    for (i, (xs, ys)) in bounds.into_iter().enumerate().filter_map(|(i, b)| Some((i, b?))) {
+        for ty in ys.start / TILE_SIZE..=(ys.end - 1) / TILE_SIZE {
+            for tx in xs.start / TILE_SIZE..=(xs.end - 1) / TILE_SIZE {
+                bins[ty * across + tx].push(i);
+            }
+        }
+    }
+
+    tiles.par_iter_mut().zip(bins.par_iter()).for_each(|(tile, bin)| {
+        let region = tile.region.clone();
+
+        for &i in bin {
+            let (clip, varyings) = &triangles[i];
+            raster::rasterize_in(shape, &region, clip, varyings, |fragment| {
+                if fragment.depth > tile.get_z(fragment.x, fragment.y) {
+                    if let Some(col) = shade(i, &fragment) {
+                        tile.set_pixel(fragment.x, fragment.y, &col);
+                        tile.set_z(fragment.x, fragment.y, fragment.depth);
+                    }
+                }
+            });
+        }
+    });
+
+    for tile in &tiles {
+        surface.put_tile(tile);
+    }
+}
+
+/// [returns] how many pixels across `sphere` (in model space) appears on `surface`, or infinity if
+///           the camera is inside it.
+pub fn projected_size(
+    sphere: &BoundingSphere,
+    model_view: &na::Similarity3<f32>,
+    proj: &na::Perspective3<f32>,
+    surface: &Surface
+) -> f32 {
+    let center = model_view.transform_point(&sphere.center);
+    let radius = sphere.radius * model_view.scaling();
+
+    // The camera looks down -z.
+    let depth = -center.z;
+    if depth <= radius {
+        return f32::INFINITY;
+    }
+
+    // The projected radius in NDC (which spans 2 units from the top of the surface to the bottom).
+    let radius_ndc = radius * proj.as_matrix().m22 / depth;
+    radius_ndc * surface.shape.1 as f32
+}
+
+/// Renders `model` with the `MatcapShader` (see `render_model_with`).
 pub fn render_model(
     model: &Model,
     camera: &CameraInfo,
     proj: &na::Perspective3<f32>,
     surface: &mut Surface
 ) {
-    let view = &camera.view_matrix;
+    render_model_with(model, camera, proj, surface, &MatcapShader, &MatcapShader);
+}
 
-    let proj = proj.to_homogeneous();
+/// Renders `model` with the given shaders, using the least detailed of its meshes that is allowed
+/// at its size on screen (see `Model::mesh_for_size`).
+pub fn render_model_with<VS: VertexShader>(
+    model: &Model,
+    camera: &CameraInfo,
+    proj: &na::Perspective3<f32>,
+    surface: &mut Surface,
+    vertex_shader: &VS,
+    fragment_shader: &impl FragmentShader<VS::Varying>
+) {
+    let model_view = camera.view_matrix * model.transform;
 
-    let light = Pt3::from([0.0, -100.0, 50.0]);
+    let mesh = if model.lods.is_empty() {
+        &model.mesh
+    } else {
+        model.mesh_for_size(projected_size(&model.bounding_sphere(), &model_view, proj, surface))
+    };
 
-    let modelView = view * model.transform;
+    let uniforms = Uniforms { model, mesh, model_view, projection: *proj, viewport: surface.shape };
+    let proj = proj.to_homogeneous();
 
-    let points_view : Vec<Pt3> = model.mesh.vertices.par_iter().map(
-        |v| { modelView.transform_point(&v.vtx.0) }
+    let points_view : Vec<Pt3> = (0..mesh.vertices.len()).into_par_iter().map(
+        |v| { vertex_shader.position(&uniforms, v) }
     ).collect();
 
     let points_ndc : Vec<Pt3> = points_view.par_iter().map(
@@ -414,12 +547,8 @@ pub fn render_model(
 
     let screen_ray = camera.screen_ray(&Px2::from([0, 0])).1;
 
-    // This is known as back-face culling
-    // if is_back_facing(&[p0_view, p1_view, p2_view], &screen_ray) {
-    // }
-
-    let mut clipped : Vec<Triangle<Pt3>> = model.mesh.faces.par_iter().filter_map(
-        |face| {
+    let mut clipped : Vec<ShadedTriangle<VS::Varying>> = mesh.faces.par_iter().enumerate().filter_map(
+        |(f, face)| {
             let should_discard =
                 out_of_bounds(&points_ndc[face.vertices[0]]) &&
                 out_of_bounds(&points_ndc[face.vertices[1]]) &&
@@ -429,17 +558,18 @@ pub fn render_model(
                 return None;
             }
 
-            let tri_view = [
-                points_view[face.vertices[0]],
-                points_view[face.vertices[1]],
-                points_view[face.vertices[2]]
-            ];
+            let tri_view = face.vertices.map(|v| points_view[v]);
 
+            // This is known as back-face culling
             if is_back_facing(&tri_view, &screen_ray) {
                 return None;
             }
 
-            Some(Triangle(tri_view))
+            Some(ShadedTriangle {
+                tri: Triangle(tri_view),
+                face: f,
+                corners: [0, 1, 2].map(|corner| vertex_shader.varying(&uniforms, f, corner, &tri_view))
+            })
         }
     ).collect();
 
@@ -456,24 +586,22 @@ pub fn render_model(
         let retained = clipped
             .par_iter()
             .filter_map(
-                |tri| -> Option<Vec<Triangle<Pt3>>> {
-                    match clip_triangle(plane, &tri) {
+                |shaded| -> Option<Vec<ShadedTriangle<VS::Varying>>> {
+                    match clip_triangle(plane, &shaded.tri) {
                         None => { None },
 
                         Some(foo) => {
                             match foo {
                                 (ClippedTriangle::NoClip, None) => {
-                                    Some(vec![tri.clone()])
+                                    Some(vec![shaded.clone()])
                                 },
 
                                 (ClippedTriangle::DoubleReplacement(result), None) => {
-                                    Some(vec![result.tri])
+                                    Some(vec![shaded.piece(result.tri)])
                                 },
 
                                 (ClippedTriangle::SingleReplacement(new_triangle_1), Some(ClippedTriangle::DoubleReplacement(new_triangle_2))) => {
-                                    Some(vec![new_triangle_1.tri, new_triangle_2.tri])
-                                    // retained.push(new_triangle_1.tri);
-                                    // retained.push(new_triangle_2.tri);
+                                    Some(vec![shaded.piece(new_triangle_1.tri), shaded.piece(new_triangle_2.tri)])
                                 },
                                 _ => { panic!("unhandled case!");}
                             }
@@ -485,37 +613,48 @@ pub fn render_model(
         clipped = retained;
     }
 
-    // todo: decide on the right time to operate upon indices
-    for tri in clipped {
-        let p0_view = tri.0[0];
-        let p1_view = tri.0[1];
-        let p2_view = tri.0[2];
-
-        // todo: this will be replaced by a fragment shader
-        let d1 = p1_view - p0_view;
-        let d2 = p2_view - p0_view;
-        let normal = d1.cross(&d2).xyz().normalize();
-
-        // let light_ray = (p0_view - light).normalize();
-        //
-        // let theta = (normal.dot(&light_ray) / (normal.norm() * light_ray.norm())).acos();
-        // let theta_mult = theta / pi::<f32>();
+    let faces : Vec<usize> = clipped.iter().map(|shaded| shaded.face).collect();
+    let triangles : Vec<ClipTriangle<VS::Varying>> = clipped.into_par_iter()
+        .map(|shaded| (shaded.tri.0.map(|p| proj * p.to_homogeneous()), shaded.corners))
+        .collect();
 
-        // let normal_model = view.inverse_transform_vector((&normal).into());
+    render_tris_tiled(surface, &triangles, |i, fragment| {
+        fragment_shader.color(&uniforms, faces[i], fragment)
+    });
+}
 
-        let col = model.texture.deref().sample_normal(&normal.xyz()).0;
+/// Renders every model in `scene` as seen by the camera at node `camera`, sharing the z-buffer of
+/// `surface` (see `render_scene_from`).
+///
+/// [note] This panics if the node has no camera.
+pub fn render_scene(scene: &Scene, camera: usize, surface: &mut Surface) {
+    let (view, proj) = scene.camera(camera).unwrap_or_else(|| panic!("node {} has no camera", camera));
+    render_scene_from(scene, &view, &proj, surface);
+}
 
-        // let col = [
-        //     (col[0] as f32 * theta_mult) as u8,
-        //     (col[1] as f32 * theta_mult) as u8,
-        //     (col[2] as f32 * theta_mult) as u8
-        // ];
+/// Renders every model in `scene` as seen by `camera`, sharing the z-buffer of `surface`.
+///
+/// Scenes without lights are shaded with each model's matcap (as `render_model` does), and those
+/// with lights by the `LitShader`.
+///
+pub fn render_scene_from(
+    scene: &Scene,
+    camera: &CameraInfo,
+    proj: &na::Perspective3<f32>,
+    surface: &mut Surface
+) {
+    let lights : Vec<DirectionalLight> = scene.world_lights().into_iter().map(|(direction, light)| DirectionalLight {
+        direction: camera.view_matrix.rotation * direction,
+        color: na::Vector3::from(light.color.map(|c| c as f32 / 255.0)) * light.intensity
+    }).collect();
 
-        // Step 3: convert the triangle into NDC space.
-        let p0 = proj.transform_point(&p0_view);
-        let p1 = proj.transform_point(&p1_view);
-        let p2 = proj.transform_point(&p2_view);
+    let lit = LitShader { lights, ambient: scene.ambient };
 
-        render_tri(surface, &Triangle([p0, p1, p2]), &col);
+    for model in scene.world_models() {
+        if lit.lights.is_empty() {
+            render_model(&model, camera, proj, surface);
+        } else {
+            render_model_with(&model, camera, proj, surface, &lit, &lit);
+        }
     }
 }
@@ -0,0 +1,226 @@
+use std::collections::HashMap;
+
+use vox_fwd::{Pt2, Vec3};
+
+use crate::model::{FaceInfo, Mesh, VertexInfo, VertexModel};
+
+/// One side of an edge, running along the boundary of a face (following its winding).
+///
+/// The corner attributes of the face at `origin` are stored here too.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct HalfEdge {
+    pub origin: usize,
+
+    /// The half-edge running the other way along the same edge, in the neighbouring face. None on
+    /// the boundary (and on non-manifold edges, which can't be represented).
+    pub twin: Option<usize>,
+
+    pub next: usize,
+    pub prev: usize,
+    pub face: usize,
+
+    // Optional indices into `HalfEdgeMesh::normals` and `HalfEdgeMesh::uvs`.
+    pub normal: Option<usize>,
+    pub uv: Option<usize>
+}
+
+#[derive(Copy, Clone)]
+pub struct HalfEdgeVertex {
+    pub vtx: VertexModel,
+    pub color: Option<[u8; 3]>,
+
+    /// An outgoing half-edge, which is a boundary one if the vertex is on the boundary. None if the
+    /// vertex isn't used by any face.
+    pub half_edge: Option<usize>
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct HalfEdgeFace {
+    /// The half-edge leaving the face's first corner.
+    pub half_edge: usize,
+    pub color: Option<[u8; 3]>,
+    pub part: usize
+}
+
+/// Defines a half-edge mesh representation, for answering adjacency questions in constant time.
+///
+/// see: https://en.wikipedia.org/wiki/Doubly_connected_edge_list
+///
+/// [note] Edges shared by more than two faces (or by two faces wound the same way) are left without
+///        twins, so they look like boundary edges. Likewise, walking around a vertex where two fans
+///        of faces meet at a point only visits one of them.
+///
+#[derive(Clone, Default)]
+pub struct HalfEdgeMesh {
+    pub half_edges: Vec<HalfEdge>,
+    pub vertices: Vec<HalfEdgeVertex>,
+    pub faces: Vec<HalfEdgeFace>,
+    pub parts: Vec<String>,
+    pub normals: Vec<Vec3>,
+    pub uvs: Vec<Pt2>
+}
+
+impl HalfEdgeMesh {
+    pub fn from_mesh(mesh: &Mesh) -> Self {
+        let mut half_edges = Vec::with_capacity(3 * mesh.faces.len());
+        let mut faces = Vec::with_capacity(mesh.faces.len());
+
+        for (face_i, face) in mesh.faces.iter().enumerate() {
+            let first = half_edges.len();
+
+            for corner in 0..3 {
+                half_edges.push(HalfEdge {
+                    origin: face.vertices[corner],
+                    twin: None,
+                    next: first + (corner + 1) % 3,
+                    prev: first + (corner + 2) % 3,
+                    face: face_i,
+                    normal: face.normals.map(|n| n[corner]),
+                    uv: face.uvs.map(|uv| uv[corner])
+                });
+            }
+
+            faces.push(HalfEdgeFace { half_edge: first, color: face.color, part: face.part });
+        }
+
+        // Pair up the half-edges that run in opposite directions along each edge.
+        let mut directed : HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+        for (h, half_edge) in half_edges.iter().enumerate() {
+            directed.entry((half_edge.origin, half_edges[half_edge.next].origin)).or_default().push(h);
+        }
+
+        for h in 0..half_edges.len() {
+            let (a, b) = (half_edges[h].origin, half_edges[half_edges[h].next].origin);
+            if a == b {
+                continue;
+            }
+
+            if let (Some([_]), Some([twin])) = (directed.get(&(a, b)).map(|v| &v[..]), directed.get(&(b, a)).map(|v| &v[..])) {
+                half_edges[h].twin = Some(*twin);
+            }
+        }
+
+        let mut vertices : Vec<HalfEdgeVertex> = mesh.vertices.iter()
+            .map(|v| HalfEdgeVertex { vtx: v.vtx, color: v.color, half_edge: None })
+            .collect();
+
+        for (h, half_edge) in half_edges.iter().enumerate() {
+            let vertex = &mut vertices[half_edge.origin];
+            let is_boundary = half_edge.twin.is_none();
+
+            if vertex.half_edge.is_none() || is_boundary {
+                vertex.half_edge = Some(h);
+            }
+        }
+
+        Self {
+            half_edges,
+            vertices,
+            faces,
+            parts: mesh.parts.clone(),
+            normals: mesh.normals.clone(),
+            uvs: mesh.uvs.clone()
+        }
+    }
+
+    pub fn to_mesh(&self) -> Mesh {
+        let mut vertices : Vec<VertexInfo> = self.vertices.iter()
+            .map(|v| VertexInfo { vtx: v.vtx, faces: vec![], color: v.color })
+            .collect();
+
+        let faces = self.faces.iter().enumerate().map(|(face_i, face)| {
+            let hs = self.face_half_edges(face_i);
+            let corners = |attribute: fn(&HalfEdge) -> Option<usize>| -> Option<[usize; 3]> {
+                Some([attribute(&self.half_edges[hs[0]])?, attribute(&self.half_edges[hs[1]])?, attribute(&self.half_edges[hs[2]])?])
+            };
+
+            let vs = hs.map(|h| self.half_edges[h].origin);
+            for v in vs {
+                vertices[v].faces.push(face_i);
+            }
+
+            FaceInfo {
+                vertices: vs,
+                color: face.color,
+                part: face.part,
+                normals: corners(|h| h.normal),
+                uvs: corners(|h| h.uv)
+            }
+        }).collect();
+
+        Mesh {
+            faces,
+            vertices,
+            parts: self.parts.clone(),
+            normals: self.normals.clone(),
+            uvs: self.uvs.clone()
+        }
+    }
+
+    /// [returns] the vertex that half-edge `h` points to.
+    pub fn destination(&self, h: usize) -> usize {
+        self.half_edges[self.half_edges[h].next].origin
+    }
+
+    pub fn is_boundary_edge(&self, h: usize) -> bool {
+        self.half_edges[h].twin.is_none()
+    }
+
+    pub fn is_boundary_vertex(&self, v: usize) -> bool {
+        self.vertices[v].half_edge.is_none_or(|h| self.is_boundary_edge(h))
+    }
+
+    /// [returns] one half-edge per edge (the one with the lower index, where there are two).
+    pub fn edges(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.half_edges.len()).filter(|&h| self.half_edges[h].twin.is_none_or(|twin| h < twin))
+    }
+
+    /// [returns] the half-edges of a face, starting at its first corner.
+    pub fn face_half_edges(&self, f: usize) -> [usize; 3] {
+        let h0 = self.faces[f].half_edge;
+        let h1 = self.half_edges[h0].next;
+        [h0, h1, self.half_edges[h1].next]
+    }
+
+    pub fn face_vertices(&self, f: usize) -> [usize; 3] {
+        self.face_half_edges(f).map(|h| self.half_edges[h].origin)
+    }
+
+    /// [returns] the faces that share an edge with face `f`.
+    pub fn face_neighbours(&self, f: usize) -> impl Iterator<Item = usize> + '_ {
+        self.face_half_edges(f).into_iter().filter_map(|h| Some(self.half_edges[self.half_edges[h].twin?].face))
+    }
+
+    /// [returns] the half-edges leaving vertex `v`, walking around it in the direction of the
+    ///           faces' winding (starting from the boundary, if `v` is on one).
+    pub fn outgoing(&self, v: usize) -> impl Iterator<Item = usize> + '_ {
+        let start = self.vertices[v].half_edge;
+
+        std::iter::successors(start, move |&h| {
+            let next = self.half_edges[self.half_edges[h].prev].twin?;
+            if Some(next) == start { None } else { Some(next) }
+        })
+    }
+
+    /// [returns] the faces around vertex `v`, in order.
+    pub fn vertex_faces(&self, v: usize) -> impl Iterator<Item = usize> + '_ {
+        self.outgoing(v).map(|h| self.half_edges[h].face)
+    }
+
+    /// [returns] the vertices joined to vertex `v` by an edge (its one-ring), in order.
+    pub fn vertex_neighbours(&self, v: usize) -> Vec<usize> {
+        let outgoing : Vec<usize> = self.outgoing(v).collect();
+        let mut neighbours : Vec<usize> = outgoing.iter().map(|&h| self.destination(h)).collect();
+
+        // On the boundary, the last face's incoming edge leads to one more neighbour.
+        if let Some(&last) = outgoing.last() {
+            let incoming = self.half_edges[last].prev;
+            if self.is_boundary_edge(incoming) {
+                neighbours.push(self.half_edges[incoming].origin);
+            }
+        }
+
+        neighbours
+    }
+}
@@ -1,8 +1,17 @@
 pub mod camera;
 pub mod clipping;
+pub mod decimate;
 pub mod geometry;
 pub mod grr;
+pub mod halfedge;
+pub mod measure;
 pub mod raster;
+pub mod scene;
+pub mod shader;
+pub mod smooth;
 pub mod surface;
 pub mod texture;
+pub mod validate;
+pub mod weld;
+pub mod normals;
 pub mod model;
@@ -0,0 +1,226 @@
+use nalgebra as na;
+
+use vox_fwd::{Pt3, Vec3};
+
+use crate::model::{Mesh, Model};
+
+/// An axis-aligned bounding box.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Aabb {
+    pub min: Pt3,
+    pub max: Pt3
+}
+
+impl Aabb {
+    /// [returns] the smallest box around `points`, or None if there aren't any.
+    pub fn from_points<'a, I: IntoIterator<Item = &'a Pt3>>(points: I) -> Option<Self> {
+        points.into_iter().fold(None, |aabb, p| Some(match aabb {
+            None => Aabb { min: *p, max: *p },
+            Some(Aabb { min, max }) => Aabb { min: min.inf(p), max: max.sup(p) }
+        }))
+    }
+
+    pub fn center(&self) -> Pt3 {
+        na::center(&self.min, &self.max)
+    }
+
+    pub fn size(&self) -> Vec3 {
+        self.max - self.min
+    }
+
+    pub fn volume(&self) -> f32 {
+        self.size().product()
+    }
+}
+
+/// An oriented bounding box: a box of size `2 * half_extents` along the columns of `axes`, around
+/// `center`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Obb {
+    pub center: Pt3,
+    pub axes: na::Rotation3<f32>,
+    pub half_extents: Vec3
+}
+
+impl Obb {
+    /// [returns] the smallest box around `points` with the given `axes`, or None if there aren't
+    ///           any points.
+    fn around<'a, I: IntoIterator<Item = &'a Pt3>>(points: I, axes: na::Rotation3<f32>) -> Option<Self> {
+        // Find the axis-aligned box in the frame of the axes, then bring it back.
+        let local : Vec<Pt3> = points.into_iter().map(|p| axes.inverse_transform_point(p)).collect();
+        let aabb = Aabb::from_points(&local)?;
+
+        Some(Obb { center: axes * aabb.center(), axes, half_extents: aabb.size() / 2.0 })
+    }
+
+    pub fn volume(&self) -> f32 {
+        8.0 * self.half_extents.product()
+    }
+
+    /// [returns] the corners of the box.
+    pub fn corners(&self) -> [Pt3; 8] {
+        [0, 1, 2, 3, 4, 5, 6, 7].map(|i| {
+            let sign = |bit: usize| if i & (1 << bit) == 0 { -1.0 } else { 1.0 };
+            let offset = Vec3::new(sign(0), sign(1), sign(2)).component_mul(&self.half_extents);
+            self.center + self.axes * offset
+        })
+    }
+}
+
+/// The second moment ∫ x xᵀ dV of the unit tetrahedron (0, e1, e2, e3).
+///
+/// see: Blow & Binstock, "How to find the inertia tensor (or other mass properties) of a 3D solid
+///      body represented by a triangle mesh" (2004)
+///
+fn canonical_covariance() -> na::Matrix3<f64> {
+    na::Matrix3::new(
+        2.0, 1.0, 1.0,
+        1.0, 2.0, 1.0,
+        1.0, 1.0, 2.0
+    ) / 120.0
+}
+
+impl Mesh {
+    /// [returns] the corners of each face, in f64 to keep the sums below accurate.
+    fn face_corners(&self) -> impl Iterator<Item = [na::Vector3<f64>; 3]> + '_ {
+        self.faces.iter().map(|f| f.vertices.map(|v| self.vertices[v].vtx.0.coords.cast::<f64>()))
+    }
+
+    /// [returns] the volume enclosed by the mesh.
+    ///
+    /// [note]    This assumes the mesh is closed and its faces point outward (see `validate` and
+    ///           `repair`). Inside-out meshes have a negative volume.
+    pub fn volume(&self) -> f32 {
+        // The sum of the signed volumes of the tetrahedra between each face and the origin.
+        self.face_corners().map(|[a, b, c]| a.dot(&b.cross(&c))).sum::<f64>() as f32 / 6.0
+    }
+
+    pub fn surface_area(&self) -> f32 {
+        self.face_corners().map(|[a, b, c]| (b - a).cross(&(c - a)).norm()).sum::<f64>() as f32 / 2.0
+    }
+
+    /// [returns] the volume and the first and second moments (∫ x dV and ∫ x xᵀ dV) of the solid.
+    fn moments(&self) -> (f64, na::Vector3<f64>, na::Matrix3<f64>) {
+        let mut volume = 0.0;
+        let mut first = na::Vector3::zeros();
+        let mut second = na::Matrix3::zeros();
+
+        for [a, b, c] in self.face_corners() {
+            // The tetrahedron (origin, a, b, c) is the unit one transformed by [a b c].
+            let transform = na::Matrix3::from_columns(&[a, b, c]);
+            let det = transform.determinant();
+
+            volume += det / 6.0;
+            first += (a + b + c) * det / 24.0;
+            second += transform * canonical_covariance() * transform.transpose() * det;
+        }
+
+        (volume, first, second)
+    }
+
+    /// [returns] the centre of mass of the solid (with uniform density), or None if it encloses no
+    ///           volume.
+    pub fn centroid(&self) -> Option<Pt3> {
+        let (volume, first, _) = self.moments();
+        if volume == 0.0 {
+            return None;
+        }
+
+        Some(Pt3::from((first / volume).cast::<f32>()))
+    }
+
+    /// [returns] the inertia tensor of the solid about its centre of mass, for a density of one (so
+    ///           multiply by the density for real units), or None if it encloses no volume.
+    pub fn inertia_tensor(&self) -> Option<na::Matrix3<f32>> {
+        let (volume, first, second) = self.moments();
+        if volume == 0.0 {
+            return None;
+        }
+
+        // Move the second moment to the centre of mass, then turn it into the inertia tensor.
+        let centroid = first / volume;
+        let second = second - centroid * centroid.transpose() * volume;
+
+        Some((na::Matrix3::identity() * second.trace() - second).cast::<f32>())
+    }
+
+    /// [returns] the axis-aligned bounding box of the vertices, or None if there aren't any.
+    pub fn aabb(&self) -> Option<Aabb> {
+        Aabb::from_points(self.vertices.iter().map(|v| &v.vtx.0))
+    }
+
+    /// [returns] an oriented bounding box of the vertices, or None if there aren't any.
+    ///
+    /// The box is lined up with the principal axes of the solid (or of the vertices, for meshes that
+    /// don't enclose a volume), unless the axis-aligned box is smaller. This is a good fit for most
+    /// shapes, though not always the smallest possible box.
+    ///
+    pub fn obb(&self) -> Option<Obb> {
+        let points = || self.vertices.iter().map(|v| &v.vtx.0);
+
+        let covariance = match self.moments() {
+            (volume, first, second) if volume != 0.0 => {
+                let centroid = first / volume;
+                second / volume - centroid * centroid.transpose()
+            }
+            _ => {
+                let n = self.vertices.len() as f64;
+                let mean = points().map(|p| p.coords.cast::<f64>()).sum::<na::Vector3<f64>>() / n;
+                points().map(|p| (p.coords.cast::<f64>() - mean) * (p.coords.cast::<f64>() - mean).transpose()).sum::<na::Matrix3<f64>>() / n
+            }
+        };
+
+        let mut axes = na::SymmetricEigen::new(covariance.cast::<f32>()).eigenvectors;
+        if axes.determinant() < 0.0 {
+            axes.column_mut(2).neg_mut();
+        }
+
+        let principal = Obb::around(points(), na::Rotation3::from_matrix_unchecked(axes))?;
+        let aligned = Obb::around(points(), na::Rotation3::identity())?;
+
+        Some(if aligned.volume() <= principal.volume() { aligned } else { principal })
+    }
+}
+
+impl Model {
+    /// [returns] the volume of the mesh, as scaled by the model's transform.
+    pub fn volume(&self) -> f32 {
+        self.mesh.volume() * self.transform.scaling().powi(3)
+    }
+
+    pub fn surface_area(&self) -> f32 {
+        self.mesh.surface_area() * self.transform.scaling().powi(2)
+    }
+
+    /// [returns] the centre of mass of the mesh, in world space.
+    pub fn centroid(&self) -> Option<Pt3> {
+        Some(self.transform * self.mesh.centroid()?)
+    }
+
+    /// [returns] the inertia tensor of the mesh about its centre of mass, in world space (and with
+    ///           a density of one).
+    pub fn inertia_tensor(&self) -> Option<na::Matrix3<f32>> {
+        // Mass goes with the cube of the scale, and the squared distances with its square.
+        let rotation = self.transform.isometry.rotation.to_rotation_matrix();
+        let inertia = self.mesh.inertia_tensor()?;
+
+        Some(rotation * inertia * rotation.transpose() * self.transform.scaling().powi(5))
+    }
+
+    /// [returns] the axis-aligned bounding box of the model's vertices, in world space.
+    pub fn aabb(&self) -> Option<Aabb> {
+        let points : Vec<Pt3> = self.mesh.vertices.iter().map(|v| self.transform * v.vtx.0).collect();
+        Aabb::from_points(&points)
+    }
+
+    /// [returns] the oriented bounding box of the mesh (see `Mesh::obb`), in world space.
+    pub fn obb(&self) -> Option<Obb> {
+        let obb = self.mesh.obb()?;
+
+        Some(Obb {
+            center: self.transform * obb.center,
+            axes: self.transform.isometry.rotation.to_rotation_matrix() * obb.axes,
+            half_extents: obb.half_extents * self.transform.scaling()
+        })
+    }
+}
@@ -1,12 +1,26 @@
 use nalgebra as na;
 use ordered_float::OrderedFloat;
 use std::collections::HashMap;
+use std::convert::Infallible;
+use std::io::Read;
 use std::sync::Arc;
 
-use vox_fwd::{Vec3, Pt3};
-use vox_stl::fwd::Facet;
+use image::RgbaImage;
 
-use crate::texture::MatcapTexture;
+use vox_fwd::{Vec3, Pt2, Pt3};
+use vox_gltf::fwd::{Material as GltfMaterial, Primitive as GltfPrimitive, Scene as GltfScene};
+use vox_3mf::fwd::{Component, Object, ThreeMf};
+use vox_obj::fwd::{FaceVertex, ObjData};
+use vox_obj::triangulate::triangulate_polygon;
+use vox_off::fwd::OffData;
+use vox_ply::fwd::{Element, Format, Ply, PropertyDef, PropertyType, ScalarType, Value};
+use vox_ply::ply::PlyError;
+use vox_stl::color::{ColorConvention, HeaderColors};
+use vox_stl::fwd::{Facet, Solid};
+use vox_stl::stl::{FacetReader, StlError};
+
+use crate::clipping::BoundingSphere;
+use crate::texture::{MatcapTexture, Texture2D};
 
 #[derive(Copy, Clone)]
 pub struct VertexModel(pub Pt3);
@@ -25,38 +39,241 @@ impl VertexModel {
 #[derive(Clone)]
 pub struct VertexInfo {
     pub vtx: VertexModel,
-    pub faces: Vec<usize>
+    pub faces: Vec<usize>,
+
+    // An optional colour for the vertex (i.e. from a scanned PLY), used for faces without their own.
+    pub color: Option<[u8; 3]>
 }
 
 #[derive(Copy, Clone)]
 pub struct FaceInfo {
-    pub vertices: [usize; 3]
+    pub vertices: [usize; 3],
+
+    // An optional colour for the face (i.e. from a coloured binary STL), which tints the texture.
+    pub color: Option<[u8; 3]>,
+
+    // The index (into `Mesh::parts`) of the part this face belongs to.
+    pub part: usize,
+
+    // Optional per-corner indices into `Mesh::normals` and `Mesh::uvs`.
+    pub normals: Option<[usize; 3]>,
+    pub uvs: Option<[usize; 3]>
 }
 
 /// Defines a face-vertex mesh representation.
 ///
 /// see: https://en.wikipedia.org/wiki/Polygon_mesh#Face-vertex_meshes
 ///
+/// A mesh may be made up of several named parts (i.e. the solids of a CAD assembly), which share
+/// vertices where they touch. A mesh built from a single unnamed source has a single part named "".
+///
+/// Normals and texture coordinates are stored separately from the vertices (as in OBJ files), so
+/// that vertices stay shared across hard edges and UV seams. Texture coordinates have v pointing up
+/// (again as in OBJ files), whatever the convention of the source.
+///
 #[derive(Clone)]
 pub struct Mesh {
     pub faces: Vec<FaceInfo>,
-    pub vertices: Vec<VertexInfo>
+    pub vertices: Vec<VertexInfo>,
+    pub parts: Vec<String>,
+    pub normals: Vec<Vec3>,
+    pub uvs: Vec<Pt2>
 }
 
 impl Mesh {
     pub fn get_vertex(&self, i: usize) -> &VertexModel {
         &self.vertices[i].vtx
     }
+
+    /// [returns] a mesh holding only the faces for which `keep` is true, with the vertices
+    ///           re-indexed (and unused ones dropped).
+    pub fn filter_faces(&self, keep: impl Fn(&FaceInfo) -> bool) -> Mesh {
+        let mut remap = vec![usize::MAX; self.vertices.len()];
+        let mut vertices : Vec<VertexInfo> = vec![];
+        let mut faces = vec![];
+
+        for face in self.faces.iter().filter(|f| keep(f)) {
+            let face_i = faces.len();
+
+            let vs = face.vertices.map(|v| {
+                if remap[v] == usize::MAX {
+                    remap[v] = vertices.len();
+                    vertices.push(VertexInfo{ vtx: self.vertices[v].vtx, faces: vec![], color: self.vertices[v].color });
+                }
+                vertices[remap[v]].faces.push(face_i);
+                remap[v]
+            });
+
+            faces.push(FaceInfo{ vertices: vs, ..*face });
+        }
+
+        Mesh {
+            faces,
+            vertices,
+            parts: self.parts.clone(),
+            normals: self.normals.clone(),
+            uvs: self.uvs.clone()
+        }
+    }
+
+    /// [returns] one mesh per part, in the order of `parts`.
+    pub fn split_parts(&self) -> Vec<Mesh> {
+        (0..self.parts.len()).map(|part| {
+            let mut mesh = self.filter_faces(|f| f.part == part);
+            mesh.parts = vec![self.parts[part].clone()];
+            mesh.faces.iter_mut().for_each(|f| f.part = 0);
+            mesh
+        }).collect()
+    }
+}
+
+/// A less detailed version of a model's mesh, drawn in its place once the model is small enough on
+/// screen.
+///
+/// The mesh can only be set through `new`, so that its bounding sphere (which is worked out once,
+/// rather than every frame) always matches it.
+///
+#[derive(Clone)]
+pub struct Lod {
+    mesh: Arc<Mesh>,
+
+    // The largest size (across the model's bounding sphere, in pixels) this mesh is drawn at.
+    pub max_pixels: f32,
+
+    bounding_sphere: BoundingSphere
+}
+
+impl Lod {
+    pub fn new(mesh: Arc<Mesh>, max_pixels: f32) -> Self {
+        let bounding_sphere = mesh_bounding_sphere(&mesh);
+        Lod { mesh, max_pixels, bounding_sphere }
+    }
+
+    pub fn mesh(&self) -> &Arc<Mesh> {
+        &self.mesh
+    }
+}
+
+/// [returns] the bounding sphere of the vertices of `mesh`.
+fn mesh_bounding_sphere(mesh: &Mesh) -> BoundingSphere {
+    let points : Vec<Pt3> = mesh.vertices.iter().map(|v| v.vtx.0).collect();
+    BoundingSphere::from(&points)
 }
 
 #[derive(Clone)]
 pub struct Model {
     pub mesh: Arc<Mesh>,
     pub transform: na::Similarity3<f32>,
-    pub texture: Arc<MatcapTexture>
+    pub texture: Arc<MatcapTexture>,
+
+    // An optional base-colour texture (i.e. from a glTF material), sampled with the face UVs.
+    pub base_color: Option<Arc<Texture2D>>,
+
+    // Optional levels of detail, from the most to the least detailed (see `grr::render_model`).
+    pub lods: Vec<Lod>
 }
 
 impl Model {
+    /// [returns] one model per part of the mesh, sharing this model's transform and texture.
+    pub fn split_parts(&self) -> Vec<Model> {
+        self.mesh.split_parts().into_iter().map(|mesh| Model {
+            mesh: Arc::new(mesh),
+            transform: self.transform,
+            texture: self.texture.clone(),
+            base_color: self.base_color.clone(),
+            lods: vec![]
+        }).collect()
+    }
+
+    /// [returns] the mesh to draw when the model's bounding sphere is `pixels` across on screen,
+    ///           which is the least detailed level of detail allowed at that size (or `mesh`).
+    pub fn mesh_for_size(&self, pixels: f32) -> &Arc<Mesh> {
+        self.lods.iter().rev()
+            .find(|lod| pixels <= lod.max_pixels)
+            .map_or(&self.mesh, |lod| &lod.mesh)
+    }
+
+    /// [returns] the bounding sphere of the model, in model space.
+    ///
+    /// [note]    This is the one stored with the least detailed level of detail, since the levels
+    ///           are expected to cover the same shape. Without any, it's worked out from `mesh`.
+    pub fn bounding_sphere(&self) -> BoundingSphere {
+        self.lods.last().map_or_else(|| mesh_bounding_sphere(&self.mesh), |lod| lod.bounding_sphere)
+    }
+
+    /// [returns] one model per primitive of every mesh instance in a glTF scene, all using the
+    ///           given matcap `texture`.
+    ///
+    /// Instances of the same mesh share it. The exception is an instance whose transform isn't a
+    /// similarity (i.e. a non-uniform scale), which can't be expressed by `Model::transform`, so
+    /// it gets its own copy of the mesh with the transform baked into the vertices.
+    ///
+    /// Faces are tinted with the material's base colour factor. Base-colour textures repeat and are
+    /// filtered trilinearly (as glTF does by default).
+    pub fn from_gltf(scene: &GltfScene, texture: Arc<MatcapTexture>) -> Vec<Model> {
+        let images : Vec<Arc<Texture2D>> = scene.images.iter()
+            .map(|i| Arc::new(Texture2D::new(RgbaImage::from_raw(i.width, i.height, i.pixels.clone()).unwrap())))
+            .collect();
+
+        let meshes : Vec<Vec<Arc<Mesh>>> = scene.meshes.iter()
+            .map(|m| m.primitives.iter().map(|p| Arc::new(Mesh::from_gltf_primitive(&m.name, p, &scene.materials))).collect())
+            .collect();
+
+        let mut models = vec![];
+
+        for instance in &scene.instances {
+            for (primitive, mesh) in scene.meshes[instance.mesh].primitives.iter().zip(&meshes[instance.mesh]) {
+                let (mesh, transform) = place(mesh, &instance.transform);
+
+                let base_color = primitive.material
+                    .and_then(|m| scene.materials[m].base_color_texture)
+                    .map(|i| images[i].clone());
+
+                models.push(Model { mesh, transform, texture: texture.clone(), base_color, lods: vec![] });
+            }
+        }
+
+        models
+    }
+
+    /// [returns] one model per mesh placed by the build items of a 3MF package, all using the
+    ///           given matcap `texture`.
+    ///
+    /// Components are flattened out, and transforms are handled as in `from_gltf`.
+    pub fn from_3mf(package: &ThreeMf, texture: Arc<MatcapTexture>) -> Vec<Model> {
+        let meshes : Vec<Arc<Mesh>> = package.objects.iter().map(|o| Arc::new(Mesh::from_3mf_object(o))).collect();
+
+        package.instances().into_iter().map(|(object, transform)| {
+            let (mesh, transform) = place(&meshes[object], &transform);
+            Model { mesh, transform, texture: texture.clone(), base_color: None, lods: vec![] }
+        }).collect()
+    }
+
+    /// [returns] a 3MF package with one object per distinct mesh and one build item per model.
+    pub fn to_3mf(models: &[Model]) -> ThreeMf {
+        let mut package = ThreeMf::default();
+        let mut meshes : Vec<&Arc<Mesh>> = vec![];
+
+        for model in models {
+            let index = match meshes.iter().position(|m| Arc::ptr_eq(m, &model.mesh)) {
+                Some(index) => index,
+                None => {
+                    meshes.push(&model.mesh);
+                    package.objects.push(model.mesh.to_3mf_object(meshes.len() as u32, model.mesh.parts.join(", ")));
+                    meshes.len() - 1
+                }
+            };
+
+            let m = model.transform.to_homogeneous();
+            package.build.push(Component {
+                object_id: package.objects[index].id,
+                transform: [0, 1, 2, 3].map(|col| [0, 1, 2, 3].map(|row| m[(row, col)]))
+            });
+        }
+
+        package
+    }
+
     pub fn triangles(&self) -> impl Iterator<Item = (&VertexModel,&VertexModel,&VertexModel)> {
         self.mesh.faces.iter().map(| face| {
             let v0 = &self.mesh.vertices[face.vertices[0]].vtx;
@@ -77,7 +294,427 @@ impl Model {
 }
 
 impl Mesh {
-    pub fn from_facets(facets: Vec<Facet>) -> Self {
+    pub fn from_facets<I: IntoIterator<Item = Facet>>(facets: I) -> Self {
+        match Self::try_from_facets(facets.into_iter().map(Ok::<Facet, Infallible>)) {
+            Ok(mesh) => mesh,
+            Err(never) => match never {}
+        }
+    }
+
+    /// Builds a mesh from a fallible stream of facets (i.e. a `vox_stl::stl::FacetReader`), so the
+    /// raw facet list never has to be held in memory. Stops at the first error.
+    pub fn try_from_facets<I, E>(facets: I) -> Result<Self, E>
+        where I: IntoIterator<Item = Result<Facet, E>>
+    {
+        let facets = facets.into_iter().map(|f| f.map(|f| (0, f)));
+        Self::build(facets, vec![String::new()], |_| None)
+    }
+
+    /// Builds a mesh with one part per solid.
+    pub fn from_solids(solids: Vec<Solid>) -> Self {
+        let parts = solids.iter().map(|s| s.name.clone()).collect();
+
+        let facets = solids.into_iter().enumerate().flat_map(|(part, solid)| {
+            solid.facets.into_iter().map(move |f| Ok::<_, Infallible>((part, f)))
+        });
+
+        match Self::build(facets, parts, |_| None) {
+            Ok(mesh) => mesh,
+            Err(never) => match never {}
+        }
+    }
+
+    /// Like `try_from_facets`, but also keeps the solids of ascii files as separate parts, and the
+    /// per-facet colours of binary files.
+    ///
+    /// The colour convention is detected from the header, and facets without a colour of their own
+    /// fall back to the object colour in the header (if there is one).
+    pub fn try_from_stl<R: Read>(mut reader: FacetReader<R>) -> Result<Self, StlError> {
+        let convention = reader.header().map(ColorConvention::detect);
+        let object_color = reader.header()
+            .and_then(|header| HeaderColors::from_header(header).color)
+            .map(|rgba| [rgba[0], rgba[1], rgba[2]]);
+
+        let facets = std::iter::from_fn(|| {
+            let facet = reader.next()?;
+            Some(facet.map(|f| (reader.solid_index(), f)))
+        });
+
+        let mut mesh = Self::build(facets, vec![], |facet| {
+            convention.and_then(|c| c.decode(facet.attribute)).or(object_color)
+        })?;
+
+        mesh.parts = reader.solid_names().to_vec();
+        Ok(mesh)
+    }
+
+    /// Builds a mesh from OBJ geometry, triangulating polygons as it goes.
+    ///
+    /// Vertices keep the indices they have in the file. There is one part per (object, group) pair
+    /// that has faces, named "object/group" (or just whichever of the two is named).
+    pub fn from_obj(data: &ObjData) -> Self {
+        let mut vertices : Vec<VertexInfo> = data.positions.iter()
+            .map(|p| VertexInfo{ vtx: VertexModel(Pt3::from(*p)), faces: vec![], color: None })
+            .collect();
+
+        let mut parts = vec![];
+        let mut part_lookup = HashMap::new();
+        let mut faces = vec![];
+
+        for (face_i, tri) in data.triangles() {
+            let face = &data.faces[face_i];
+
+            let part = *part_lookup.entry((face.object, face.group)).or_insert_with(|| {
+                let name = match (data.objects[face.object].as_str(), data.groups[face.group].as_str()) {
+                    (object, "") => object.to_string(),
+                    ("", group) => group.to_string(),
+                    (object, group) => format!("{}/{}", object, group)
+                };
+                parts.push(name);
+                parts.len() - 1
+            });
+
+            let corners = |f: fn(&FaceVertex) -> Option<usize>| -> Option<[usize; 3]> {
+                Some([f(&tri[0])?, f(&tri[1])?, f(&tri[2])?])
+            };
+
+            for v in &tri {
+                vertices[v.position].faces.push(faces.len());
+            }
+
+            faces.push(FaceInfo{
+                vertices: tri.map(|v| v.position),
+                color: None,
+                part,
+                normals: corners(|v| v.normal),
+                uvs: corners(|v| v.uv)
+            });
+        }
+
+        if parts.is_empty() {
+            parts.push(String::new());
+        }
+
+        Self {
+            faces,
+            vertices,
+            parts,
+            normals: data.normals.iter().map(|n| Vec3::from(*n)).collect(),
+            uvs: data.uvs.iter().map(|uv| Pt2::from(*uv)).collect()
+        }
+    }
+
+    /// Builds a mesh from the 'vertex' and 'face' elements of a PLY file, triangulating polygons as
+    /// it goes. Vertices keep the indices they have in the file.
+    ///
+    /// Besides x/y/z, the vertex properties nx/ny/nz, s/t (or u/v) and red/green/blue are picked up
+    /// if present, as are red/green/blue on faces. Any other elements and properties are ignored.
+    pub fn from_ply(ply: &Ply) -> Result<Self, PlyError> {
+        let missing = |element: &str, property: &str| PlyError::MissingProperty {
+            element: element.to_string(),
+            property: property.to_string()
+        };
+
+        let vertex = ply.element("vertex").ok_or_else(|| missing("vertex", "x"))?;
+        let coord = |name: &str| vertex.scalars(name).ok_or_else(|| missing("vertex", name));
+
+        let (xs, ys, zs) = (coord("x")?, coord("y")?, coord("z")?);
+        let positions : Vec<[f32; 3]> = (0..xs.len()).map(|i| [xs[i] as f32, ys[i] as f32, zs[i] as f32]).collect();
+
+        let vertex_colors = ply_colors(vertex);
+        let mut vertices : Vec<VertexInfo> = positions.iter().enumerate()
+            .map(|(i, p)| VertexInfo{
+                vtx: VertexModel(Pt3::from(*p)),
+                faces: vec![],
+                color: vertex_colors.as_ref().map(|c| c[i])
+            })
+            .collect();
+
+        let normals : Vec<Vec3> = match (vertex.scalars("nx"), vertex.scalars("ny"), vertex.scalars("nz")) {
+            (Some(nx), Some(ny), Some(nz)) => (0..nx.len()).map(|i| Vec3::new(nx[i] as f32, ny[i] as f32, nz[i] as f32)).collect(),
+            _ => vec![]
+        };
+
+        let uvs : Vec<Pt2> = match (vertex.scalars("s"), vertex.scalars("t"), vertex.scalars("u"), vertex.scalars("v")) {
+            (Some(s), Some(t), _, _) | (_, _, Some(s), Some(t)) => (0..s.len()).map(|i| Pt2::new(s[i] as f32, t[i] as f32)).collect(),
+            _ => vec![]
+        };
+
+        let mut faces = vec![];
+
+        if let Some(face) = ply.element("face") {
+            let polygons = face.lists("vertex_indices").or_else(|| face.lists("vertex_index"))
+                .ok_or_else(|| missing("face", "vertex_indices"))?;
+            let face_colors = ply_colors(face);
+
+            for (face_i, polygon) in polygons.iter().enumerate() {
+                let indices = polygon.iter().map(|&index| {
+                    if index >= 0.0 && index.fract() == 0.0 && (index as usize) < vertices.len() {
+                        Ok(index as usize)
+                    } else {
+                        Err(PlyError::BadIndex { face: face_i, index })
+                    }
+                }).collect::<Result<Vec<usize>, _>>()?;
+
+                let corners : Vec<[f32; 3]> = indices.iter().map(|&i| positions[i]).collect();
+
+                for tri in triangulate_polygon(&corners) {
+                    let vs = tri.map(|i| indices[i]);
+
+                    for &v in &vs {
+                        vertices[v].faces.push(faces.len());
+                    }
+
+                    faces.push(FaceInfo{
+                        vertices: vs,
+                        color: face_colors.as_ref().map(|c| c[face_i]),
+                        part: 0,
+                        normals: if normals.is_empty() { None } else { Some(vs) },
+                        uvs: if uvs.is_empty() { None } else { Some(vs) }
+                    });
+                }
+            }
+        }
+
+        Ok(Self{ faces, vertices, parts: vec![String::new()], normals, uvs })
+    }
+
+    /// [returns] the mesh as a PLY file with a 'vertex' and a 'face' element.
+    ///
+    /// Normals and texture coordinates are only written if they are per-vertex (as they are when
+    /// read from a PLY file), since PLY has nowhere to put per-corner ones. Colours are written if
+    /// every vertex (or face) has one.
+    pub fn to_ply(&self, format: Format) -> Ply {
+        let scalar = |name: &str, ty: ScalarType| PropertyDef { name: name.to_string(), ty: PropertyType::Scalar(ty) };
+        let rgb = || [scalar("red", ScalarType::UChar), scalar("green", ScalarType::UChar), scalar("blue", ScalarType::UChar)];
+
+        let with_normals = self.is_per_vertex(self.normals.len(), |f| f.normals);
+        let with_uvs = self.is_per_vertex(self.uvs.len(), |f| f.uvs);
+        let with_vertex_colors = !self.vertices.is_empty() && self.vertices.iter().all(|v| v.color.is_some());
+        let with_face_colors = !self.faces.is_empty() && self.faces.iter().all(|f| f.color.is_some());
+
+        let mut vertex = Element { name: "vertex".to_string(), properties: vec![], rows: vec![] };
+        vertex.properties.extend(["x", "y", "z"].map(|n| scalar(n, ScalarType::Float)));
+        if with_normals {
+            vertex.properties.extend(["nx", "ny", "nz"].map(|n| scalar(n, ScalarType::Float)));
+        }
+        if with_uvs {
+            vertex.properties.extend(["s", "t"].map(|n| scalar(n, ScalarType::Float)));
+        }
+        if with_vertex_colors {
+            vertex.properties.extend(rgb());
+        }
+
+        for (i, v) in self.vertices.iter().enumerate() {
+            let mut row : Vec<f64> = v.vtx.0.iter().map(|&c| c as f64).collect();
+            if with_normals {
+                row.extend(self.normals[i].iter().map(|&c| c as f64));
+            }
+            if with_uvs {
+                row.extend(self.uvs[i].iter().map(|&c| c as f64));
+            }
+            if let (true, Some(color)) = (with_vertex_colors, v.color) {
+                row.extend(color.map(|c| c as f64));
+            }
+            vertex.rows.push(row.into_iter().map(Value::Scalar).collect());
+        }
+
+        let mut face = Element {
+            name: "face".to_string(),
+            properties: vec![PropertyDef {
+                name: "vertex_indices".to_string(),
+                ty: PropertyType::List { count: ScalarType::UChar, item: ScalarType::Int }
+            }],
+            rows: vec![]
+        };
+        if with_face_colors {
+            face.properties.extend(rgb());
+        }
+
+        for f in &self.faces {
+            let mut row = vec![Value::List(f.vertices.iter().map(|&v| v as f64).collect())];
+            if let (true, Some(color)) = (with_face_colors, f.color) {
+                row.extend(color.map(|c| Value::Scalar(c as f64)));
+            }
+            face.rows.push(row);
+        }
+
+        Ply { format, comments: vec![], obj_info: vec![], elements: vec![vertex, face] }
+    }
+
+    /// Builds a mesh from OFF geometry, triangulating polygons as it goes. Vertices keep the
+    /// indices they have in the file, along with their normals and colours (alpha is dropped).
+    pub fn from_off(data: &OffData) -> Self {
+        let rgb = |c: &[u8; 4]| [c[0], c[1], c[2]];
+
+        let mut vertices : Vec<VertexInfo> = data.positions.iter().enumerate()
+            .map(|(i, p)| VertexInfo{
+                vtx: VertexModel(Pt3::from(*p)),
+                faces: vec![],
+                color: data.vertex_colors.get(i).map(rgb)
+            })
+            .collect();
+
+        let mut faces = vec![];
+
+        for (face_i, polygon) in data.faces.iter().enumerate() {
+            let corners : Vec<[f32; 3]> = polygon.iter().map(|&i| data.positions[i]).collect();
+
+            for tri in triangulate_polygon(&corners) {
+                let vs = tri.map(|i| polygon[i]);
+
+                for &v in &vs {
+                    vertices[v].faces.push(faces.len());
+                }
+
+                faces.push(FaceInfo{
+                    vertices: vs,
+                    color: data.face_colors.get(face_i).copied().flatten().as_ref().map(rgb),
+                    part: 0,
+                    normals: if data.normals.is_empty() { None } else { Some(vs) },
+                    uvs: None
+                });
+            }
+        }
+
+        Self {
+            faces,
+            vertices,
+            parts: vec![String::new()],
+            normals: data.normals.iter().map(|n| Vec3::from(*n)).collect(),
+            uvs: vec![]
+        }
+    }
+
+    /// [returns] the mesh as OFF geometry.
+    ///
+    /// Normals are only written if they are per-vertex. Vertex colours are written if every vertex
+    /// has one, and face colours if any face has one.
+    pub fn to_off(&self) -> OffData {
+        let rgba = |c: [u8; 3]| [c[0], c[1], c[2], 255];
+
+        let with_vertex_colors = !self.vertices.is_empty() && self.vertices.iter().all(|v| v.color.is_some());
+        let with_face_colors = self.faces.iter().any(|f| f.color.is_some());
+
+        OffData {
+            positions: self.vertices.iter().map(|v| [v.vtx.0.x, v.vtx.0.y, v.vtx.0.z]).collect(),
+            normals: if self.is_per_vertex(self.normals.len(), |f| f.normals) {
+                self.normals.iter().map(|n| [n.x, n.y, n.z]).collect()
+            } else {
+                vec![]
+            },
+            vertex_colors: if with_vertex_colors {
+                self.vertices.iter().filter_map(|v| v.color.map(rgba)).collect()
+            } else {
+                vec![]
+            },
+            faces: self.faces.iter().map(|f| f.vertices.to_vec()).collect(),
+            face_colors: if with_face_colors {
+                self.faces.iter().map(|f| f.color.map(rgba)).collect()
+            } else {
+                vec![]
+            }
+        }
+    }
+
+    /// Builds a single-part mesh (named after the object) from the mesh of a 3MF object. Any
+    /// components are ignored (see `Model::from_3mf`).
+    pub fn from_3mf_object(object: &Object) -> Self {
+        let mut vertices : Vec<VertexInfo> = object.vertices.iter()
+            .map(|p| VertexInfo{ vtx: VertexModel(Pt3::from(*p)), faces: vec![], color: None })
+            .collect();
+
+        let faces = object.triangles.iter().enumerate().map(|(face_i, &vs)| {
+            for &v in &vs {
+                vertices[v].faces.push(face_i);
+            }
+            FaceInfo{ vertices: vs, color: None, part: 0, normals: None, uvs: None }
+        }).collect();
+
+        Self { faces, vertices, parts: vec![object.name.clone()], normals: vec![], uvs: vec![] }
+    }
+
+    /// [returns] the mesh as a 3MF object with the given id and name.
+    pub fn to_3mf_object(&self, id: u32, name: String) -> Object {
+        Object {
+            id,
+            name,
+            vertices: self.vertices.iter().map(|v| [v.vtx.0.x, v.vtx.0.y, v.vtx.0.z]).collect(),
+            triangles: self.faces.iter().map(|f| f.vertices).collect(),
+            components: vec![]
+        }
+    }
+
+    /// [returns] true if the per-corner indices picked out by `corners` are those of the vertices
+    ///           (i.e. the attribute is per-vertex), for an attribute with `len` values.
+    fn is_per_vertex(&self, len: usize, corners: fn(&FaceInfo) -> Option<[usize; 3]>) -> bool {
+        len == self.vertices.len() && self.faces.iter().all(|f| corners(f) == Some(f.vertices))
+    }
+
+    /// Builds a single-part mesh (named after the glTF mesh) from a glTF primitive.
+    fn from_gltf_primitive(name: &str, primitive: &GltfPrimitive, materials: &[GltfMaterial]) -> Self {
+        let mut vertices : Vec<VertexInfo> = primitive.positions.iter()
+            .map(|p| VertexInfo{ vtx: VertexModel(Pt3::from(*p)), faces: vec![], color: None })
+            .collect();
+
+        // The factor is linear, whereas tints are applied to (sRGB) matcap colours.
+        let color = primitive.material
+            .map(|m| materials[m].base_color_factor)
+            .filter(|factor| factor[..3] != [1.0; 3])
+            .map(|factor| [0, 1, 2].map(|i| (factor[i].clamp(0.0, 1.0).powf(1.0 / 2.2) * 255.0).round() as u8));
+
+        // Attributes are only used if they cover every vertex (as the importer makes sure they do).
+        let has_normals = !primitive.normals.is_empty() && primitive.normals.len() == vertices.len();
+        let has_uvs = !primitive.uvs.is_empty() && primitive.uvs.len() == vertices.len();
+
+        let mut faces = vec![];
+
+        for tri in primitive.indices.chunks_exact(3) {
+            let vs = [tri[0] as usize, tri[1] as usize, tri[2] as usize];
+
+            // An index that is out of range can only come from a broken file, so the face is dropped.
+            if vs.iter().any(|&v| v >= vertices.len()) {
+                continue;
+            }
+
+            for &v in &vs {
+                vertices[v].faces.push(faces.len());
+            }
+
+            faces.push(FaceInfo{
+                vertices: vs,
+                color,
+                part: 0,
+                normals: has_normals.then_some(vs),
+                uvs: has_uvs.then_some(vs)
+            });
+        }
+
+        Self {
+            faces,
+            vertices,
+            parts: vec![name.to_string()],
+            normals: primitive.normals.iter().map(|n| Vec3::from(*n)).collect(),
+
+            // glTF puts the origin of texture space at the top-left.
+            uvs: primitive.uvs.iter().map(|uv| Pt2::new(uv[0], 1.0 - uv[1])).collect()
+        }
+    }
+
+    /// [returns] a copy of the mesh with `matrix` applied to its vertices (and normals).
+    fn transformed(&self, matrix: &na::Matrix4<f32>) -> Self {
+        let normal_matrix = matrix.fixed_slice::<3, 3>(0, 0).try_inverse().unwrap_or_else(na::Matrix3::identity).transpose();
+
+        let mut mesh = self.clone();
+        mesh.vertices.iter_mut().for_each(|v| v.vtx.0 = matrix.transform_point(&v.vtx.0));
+        mesh.normals.iter_mut().for_each(|n| *n = (normal_matrix * *n).normalize());
+        mesh
+    }
+
+    /// Builds the mesh from a stream of (part index, facet) pairs.
+    fn build<I, E>(facets: I, parts: Vec<String>, color_of: impl Fn(&Facet) -> Option<[u8; 3]>) -> Result<Self, E>
+        where I: IntoIterator<Item = Result<(usize, Facet), E>>
+    {
         let mut vertices : Vec<VertexInfo> = vec![];
         let mut vert_lookup = HashMap::new();
         let mut faces = vec![];
@@ -86,7 +723,8 @@ impl Mesh {
             (OrderedFloat::from(v.0[0]), OrderedFloat::from(v.0[1]), OrderedFloat::from(v.0[2]))
         };
 
-        for facet in facets {
+        for item in facets {
+            let (part, facet) = item?;
             let face_i = faces.len();
 
             // Build up the list of vertices, de-duplicating them if need be.
@@ -97,7 +735,7 @@ impl Mesh {
 
                 if !vert_lookup.contains_key(&key) {
                     vert_lookup.insert(key, vert_lookup.len());
-                    vertices.push(VertexInfo{ vtx: vert, faces: vec![] });
+                    vertices.push(VertexInfo{ vtx: vert, faces: vec![], color: None });
                 }
 
                 let vert_i = vert_lookup.get(&key).unwrap();
@@ -107,9 +745,67 @@ impl Mesh {
                 *vert_i
             }).collect();
 
-            faces.push(FaceInfo{ vertices: [vs[0], vs[1], vs[2]] });
+            faces.push(FaceInfo{
+                vertices: [vs[0], vs[1], vs[2]],
+                color: color_of(&facet),
+                part,
+                normals: None,
+                uvs: None
+            });
         }
 
-        Self{faces, vertices}
+        Ok(Self{faces, vertices, parts, normals: vec![], uvs: vec![]})
     }
 }
+
+/// [returns] the red/green/blue properties of every row of `element`, if it has them.
+///
+/// [note]    Floating point colours are taken to be in [0, 1], integer ones in [0, 255].
+fn ply_colors(element: &Element) -> Option<Vec<[u8; 3]>> {
+    let channel = |name: &str| -> Option<Vec<u8>> {
+        let i = element.property_index(name)?;
+        let scale = match element.properties[i].ty {
+            PropertyType::Scalar(ScalarType::Float | ScalarType::Double) => 255.0,
+            _ => 1.0
+        };
+        Some(element.scalars(name)?.iter().map(|v| (v * scale).round().clamp(0.0, 255.0) as u8).collect())
+    };
+
+    let (r, g, b) = (channel("red")?, channel("green")?, channel("blue")?);
+    Some((0..r.len()).map(|i| [r[i], g[i], b[i]]).collect())
+}
+
+/// [returns] `mesh` placed by the column-major `transform`, as a model would hold it.
+///
+/// [note]    Transforms that aren't similarities are baked into a copy of the mesh.
+fn place(mesh: &Arc<Mesh>, transform: &[[f32; 4]; 4]) -> (Arc<Mesh>, na::Similarity3<f32>) {
+    let matrix = na::Matrix4::from_fn(|row, col| transform[col][row]);
+
+    match similarity_from_matrix(&matrix) {
+        Some(similarity) => (mesh.clone(), similarity),
+        None => (Arc::new(mesh.transformed(&matrix)), na::Similarity3::identity())
+    }
+}
+
+/// [returns] `matrix` as a similarity (a rotation, a uniform scale and a translation), if it is one.
+fn similarity_from_matrix(matrix: &na::Matrix4<f32>) -> Option<na::Similarity3<f32>> {
+    let linear = matrix.fixed_slice::<3, 3>(0, 0).into_owned();
+
+    let det = linear.determinant();
+    if det <= 0.0 || matrix.row(3) != na::RowVector4::new(0.0, 0.0, 0.0, 1.0) {
+        return None;
+    }
+
+    let scale = det.cbrt();
+    let rotation = linear / scale;
+
+    if !(rotation.transpose() * rotation).relative_eq(&na::Matrix3::identity(), 1e-4, 1e-4) {
+        return None;
+    }
+
+    Some(na::Similarity3::from_parts(
+        na::Translation3::new(matrix[(0, 3)], matrix[(1, 3)], matrix[(2, 3)]),
+        na::UnitQuaternion::from_matrix(&rotation),
+        scale
+    ))
+}
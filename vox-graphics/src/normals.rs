@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+
+use vox_fwd::Vec3;
+
+use crate::model::Mesh;
+
+/// How much each face counts towards the normal at one of its corners.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum NormalWeighting {
+    /// By the area of the face, so that slivers barely count.
+    Area,
+
+    /// By the angle of the face at the corner, which doesn't depend on how the surface happens to
+    /// be split into triangles.
+    Angle
+}
+
+impl Mesh {
+    /// [returns] the unit normal of each face (or zero, if it has no area).
+    fn face_normals(&self) -> Vec<Vec3> {
+        self.faces.iter().map(|face| {
+            let [p0, p1, p2] = face.vertices.map(|v| self.vertices[v].vtx.0);
+            (p1 - p0).cross(&(p2 - p0)).try_normalize(0.0).unwrap_or_else(Vec3::zeros)
+        }).collect()
+    }
+
+    /// [returns] the weight of `face_i` at its corner on vertex `v`.
+    fn corner_weight(&self, face_i: usize, v: usize, weighting: NormalWeighting) -> f32 {
+        let face = &self.faces[face_i];
+        let corner = face.vertices.iter().position(|&u| u == v).unwrap();
+        let [p0, p1, p2] = [0, 1, 2].map(|i| self.vertices[face.vertices[(corner + i) % 3]].vtx.0);
+
+        match weighting {
+            NormalWeighting::Area => (p1 - p0).cross(&(p2 - p0)).norm() / 2.0,
+            NormalWeighting::Angle => (p1 - p0).angle(&(p2 - p0))
+        }
+    }
+
+    /// Replaces the normals of the mesh with ones worked out from its faces, so that curved surfaces
+    /// can be shaded smoothly.
+    ///
+    /// The normal at each corner of a face is the weighted average of the normals of the faces
+    /// around that corner's vertex, leaving out faces that meet it at more than `crease_angle`
+    /// (in radians). Hard edges stay hard, and a vertex gets one normal per smooth side of it.
+    ///
+    /// [note] A `crease_angle` of zero gives flat shading, and one of pi smooths everything.
+    ///
+    pub fn compute_normals(&mut self, crease_angle: f32, weighting: NormalWeighting) {
+        let face_normals = self.face_normals();
+        let min_cos = crease_angle.cos();
+
+        let mut normals : Vec<Vec3> = vec![];
+        let mut corners = vec![[0; 3]; self.faces.len()];
+
+        for (v, vertex) in self.vertices.iter().enumerate() {
+            // The normals at this vertex, so that corners on the same smooth side share one.
+            let mut found : HashMap<[u32; 3], usize> = HashMap::new();
+
+            for &face_i in &vertex.faces {
+                let normal = vertex.faces.iter()
+                    .filter(|&&other| face_normals[face_i].dot(&face_normals[other]) >= min_cos || other == face_i)
+                    .map(|&other| face_normals[other] * self.corner_weight(other, v, weighting))
+                    .sum::<Vec3>()
+                    .try_normalize(0.0)
+                    .unwrap_or(face_normals[face_i]);
+
+                let normal_i = *found.entry(normal.map(f32::to_bits).into()).or_insert_with(|| {
+                    normals.push(normal);
+                    normals.len() - 1
+                });
+
+                for (corner, &u) in self.faces[face_i].vertices.iter().enumerate() {
+                    if u == v {
+                        corners[face_i][corner] = normal_i;
+                    }
+                }
+            }
+        }
+
+        for (face, corners) in self.faces.iter_mut().zip(corners) {
+            face.normals = Some(corners);
+        }
+        self.normals = normals;
+    }
+}
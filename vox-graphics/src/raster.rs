@@ -1,4 +1,6 @@
+use nalgebra as na;
 use std::mem;
+use std::ops::Range;
 
 #[derive(Copy, Clone)]
 pub struct LinearIntensity {
@@ -180,3 +182,158 @@ impl Triangle2D {
         (buf_ps, buf_as)
     }
 }
+
+/// Values given at the corners of a triangle that can be interpolated across it (i.e. normals,
+/// texture coordinates or colours).
+pub trait Varying: Copy {
+    /// [returns] the sum of `values` weighted by `weights` (which add up to one).
+    fn blend(values: &[Self; 3], weights: &[f32; 3]) -> Self;
+}
+
+impl Varying for () {
+    fn blend(_: &[Self; 3], _: &[f32; 3]) -> Self {}
+}
+
+impl Varying for f32 {
+    fn blend(values: &[Self; 3], weights: &[f32; 3]) -> Self {
+        values[0] * weights[0] + values[1] * weights[1] + values[2] * weights[2]
+    }
+}
+
+impl<const D: usize> Varying for na::SVector<f32, D> {
+    fn blend(values: &[Self; 3], weights: &[f32; 3]) -> Self {
+        values[0] * weights[0] + values[1] * weights[1] + values[2] * weights[2]
+    }
+}
+
+impl<A: Varying, B: Varying> Varying for (A, B) {
+    fn blend(values: &[Self; 3], weights: &[f32; 3]) -> Self {
+        (
+            A::blend(&values.map(|v| v.0), weights),
+            B::blend(&values.map(|v| v.1), weights)
+        )
+    }
+}
+
+impl<A: Varying, B: Varying, C: Varying> Varying for (A, B, C) {
+    fn blend(values: &[Self; 3], weights: &[f32; 3]) -> Self {
+        (
+            A::blend(&values.map(|v| v.0), weights),
+            B::blend(&values.map(|v| v.1), weights),
+            C::blend(&values.map(|v| v.2), weights)
+        )
+    }
+}
+
+/// A pixel covered by a triangle, along with its depth and the interpolated varyings.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Fragment<V> {
+    pub x: usize,
+    pub y: usize,
+
+    // 1/w at the pixel, which is larger for nearer fragments.
+    pub depth: f32,
+
+    pub varying: V
+}
+
+/// [returns] twice the signed area of the triangle (a, b, p).
+fn edge_function(a: (f32, f32), b: (f32, f32), p: (f32, f32)) -> f32 {
+    (b.0 - a.0) * (p.1 - a.1) - (b.1 - a.1) * (p.0 - a.0)
+}
+
+/// A rectangle of pixels, as the ranges of columns and rows it covers.
+pub type Region = (Range<usize>, Range<usize>);
+
+/// [returns] the screen positions of the clip-space corners `clip`, on a surface of the given
+///           `shape`.
+fn to_screen(shape: (usize, usize), clip: &[na::Vector4<f32>; 3]) -> [(f32, f32); 3] {
+    // Matches `Surface::to_pixel`, where NDC x and y from -1 to 1 span the whole surface.
+    clip.map(|c| {
+        let inv_w = 1.0 / c.w;
+        ((1.0 + c.x * inv_w) * shape.0 as f32 / 2.0, (1.0 + c.y * inv_w) * shape.1 as f32 / 2.0)
+    })
+}
+
+/// [returns] the pixels of a surface of the given `shape` that `rasterize` might emit for the
+///           triangle with the clip-space corners `clip`, or None if it won't emit any.
+pub fn bounds(shape: (usize, usize), clip: &[na::Vector4<f32>; 3]) -> Option<Region> {
+    if shape.0 == 0 || shape.1 == 0 {
+        return None;
+    }
+
+    let screen = to_screen(shape, clip);
+    let area = edge_function(screen[0], screen[1], screen[2]);
+    if area == 0.0 || !area.is_finite() {
+        return None;
+    }
+
+    let bound = |values: [f32; 3], len: usize| {
+        let min = values.iter().copied().fold(f32::INFINITY, f32::min).floor().max(0.0) as usize;
+        let max = values.iter().copied().fold(f32::NEG_INFINITY, f32::max).ceil().min((len - 1) as f32).max(0.0) as usize;
+        min..max + 1
+    };
+
+    Some((bound(screen.map(|s| s.0), shape.0), bound(screen.map(|s| s.1), shape.1)))
+}
+
+/// Calls `emit` with each pixel of a surface of the given `shape` whose centre is inside the
+/// triangle with the clip-space corners `clip`, in rows from the top.
+///
+/// The `varyings` are interpolated perspective-correctly: each corner is weighted by 1/w, which
+/// (unlike the values themselves) varies linearly across the screen. Interpolating them linearly
+/// in screen space instead would warp textures on faces seen at an angle.
+///
+/// [note] The corners are expected to be in front of the camera (w > 0), i.e. after clipping.
+///        Pixels on an edge shared by two triangles are emitted for both of them.
+///
+pub fn rasterize<V: Varying>(
+    shape: (usize, usize),
+    clip: &[na::Vector4<f32>; 3],
+    varyings: &[V; 3],
+    emit: impl FnMut(Fragment<V>)
+) {
+    rasterize_in(shape, &(0..shape.0, 0..shape.1), clip, varyings, emit);
+}
+
+/// Like `rasterize`, but only emits the pixels within `region` (i.e. one tile of the surface).
+///
+/// [note] The fragments are exactly those `rasterize` emits in the region, so drawing every tile
+///        of a surface gives the same image as drawing it whole.
+pub fn rasterize_in<V: Varying>(
+    shape: (usize, usize),
+    region: &Region,
+    clip: &[na::Vector4<f32>; 3],
+    varyings: &[V; 3],
+    mut emit: impl FnMut(Fragment<V>)
+) {
+    let Some((xs, ys)) = bounds(shape, clip) else { return };
+    let xs = xs.start.max(region.0.start)..xs.end.min(region.0.end);
+    let ys = ys.start.max(region.1.start)..ys.end.min(region.1.end);
+
+    let inv_w = clip.map(|c| 1.0 / c.w);
+    let screen = to_screen(shape, clip);
+    let area = edge_function(screen[0], screen[1], screen[2]);
+
+    for y in ys {
+        for x in xs.clone() {
+            let p = (x as f32 + 0.5, y as f32 + 0.5);
+
+            // The barycentric coordinates of the pixel centre, on screen.
+            let b = [
+                edge_function(screen[1], screen[2], p) / area,
+                edge_function(screen[2], screen[0], p) / area,
+                edge_function(screen[0], screen[1], p) / area
+            ];
+
+            if b.iter().any(|&c| c < 0.0) {
+                continue;
+            }
+
+            let depth = b[0] * inv_w[0] + b[1] * inv_w[1] + b[2] * inv_w[2];
+            let weights = [0, 1, 2].map(|i| b[i] * inv_w[i] / depth);
+
+            emit(Fragment { x, y, depth, varying: V::blend(varyings, &weights) });
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use nalgebra as na;
+
+use vox_fwd::{Pt3, Vec3};
+
+use crate::camera::CameraInfo;
+use crate::model::Model;
+
+/// A light shining down the -z axis of its node (as glTF's directional lights do).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Light {
+    pub color: [u8; 3],
+
+    // How bright the light is (where 1 lights a face facing it fully in `color`).
+    pub intensity: f32
+}
+
+/// A camera looking down the -z axis of its node, with y up.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Camera {
+    pub projection: na::Perspective3<f32>
+}
+
+/// A node of a `Scene`, which places whatever it holds (and its children) relative to its parent.
+#[derive(Clone)]
+pub struct Node {
+    pub name: String,
+
+    // The transform from this node's space to its parent's (or the world's, for root nodes).
+    pub transform: na::Similarity3<f32>,
+
+    // The model's own transform is applied before the node's.
+    pub model: Option<Model>,
+    pub light: Option<Light>,
+    pub camera: Option<Camera>
+}
+
+impl Node {
+    /// [returns] an empty node, with no transform.
+    pub fn new(name: &str) -> Self {
+        Node { name: name.to_string(), transform: na::Similarity3::identity(), model: None, light: None, camera: None }
+    }
+}
+
+/// A hierarchy of nodes holding models, lights and cameras (see `grr::render_scene`).
+///
+/// Nodes are referred to by their index, and parents always come before their children, so the
+/// world transforms can be worked out in a single pass.
+///
+#[derive(Clone)]
+pub struct Scene {
+    nodes: Vec<Node>,
+    parents: Vec<Option<usize>>,
+
+    // The light that reaches every face, whichever way it faces (from 0 to 1).
+    pub ambient: f32
+}
+
+impl Default for Scene {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Scene {
+    pub fn new() -> Self {
+        Scene { nodes: vec![], parents: vec![], ambient: 0.2 }
+    }
+
+    /// [returns] the index of `node`, added as a child of `parent` (or as a root node).
+    ///
+    /// [note]    This panics if `parent` isn't in the scene.
+    pub fn add(&mut self, parent: Option<usize>, node: Node) -> usize {
+        if let Some(parent) = parent {
+            assert!(parent < self.nodes.len(), "no node {} to add a child to", parent);
+        }
+
+        self.nodes.push(node);
+        self.parents.push(parent);
+        self.nodes.len() - 1
+    }
+
+    pub fn nodes(&self) -> &[Node] {
+        &self.nodes
+    }
+
+    pub fn node(&self, i: usize) -> &Node {
+        &self.nodes[i]
+    }
+
+    pub fn node_mut(&mut self, i: usize) -> &mut Node {
+        &mut self.nodes[i]
+    }
+
+    pub fn parent(&self, i: usize) -> Option<usize> {
+        self.parents[i]
+    }
+
+    pub fn children(&self, i: usize) -> impl Iterator<Item = usize> + '_ {
+        (i + 1..self.nodes.len()).filter(move |&c| self.parents[c] == Some(i))
+    }
+
+    /// [returns] the index of the first node called `name`, if there is one.
+    pub fn find(&self, name: &str) -> Option<usize> {
+        self.nodes.iter().position(|n| n.name == name)
+    }
+
+    /// [returns] the transform from node `i`'s space to the world.
+    pub fn world_transform(&self, i: usize) -> na::Similarity3<f32> {
+        match self.parents[i] {
+            Some(parent) => self.world_transform(parent) * self.nodes[i].transform,
+            None => self.nodes[i].transform
+        }
+    }
+
+    /// [returns] the world transform of every node (see `world_transform`).
+    pub fn world_transforms(&self) -> Vec<na::Similarity3<f32>> {
+        let mut transforms : Vec<na::Similarity3<f32>> = Vec::with_capacity(self.nodes.len());
+
+        for (node, parent) in self.nodes.iter().zip(&self.parents) {
+            let transform = match parent {
+                Some(parent) => transforms[*parent] * node.transform,
+                None => node.transform
+            };
+            transforms.push(transform);
+        }
+
+        transforms
+    }
+
+    /// [returns] every model in the scene, with its transform taking it all the way to the world.
+    pub fn world_models(&self) -> Vec<Model> {
+        zip_nodes(self).filter_map(|(node, world)| {
+            let model = node.model.as_ref()?;
+            Some(Model { transform: world * model.transform, ..model.clone() })
+        }).collect()
+    }
+
+    /// [returns] the view of the camera at node `i` (ignoring any scaling), and its projection, or
+    ///           None if the node has no camera.
+    pub fn camera(&self, i: usize) -> Option<(CameraInfo, na::Perspective3<f32>)> {
+        let camera = self.nodes[i].camera?;
+        let world = self.world_transform(i).isometry;
+
+        let eye = Pt3::from(world.translation.vector);
+        let target = world * Pt3::new(0.0, 0.0, -1.0);
+
+        Some((CameraInfo { eye, target, view_matrix: world.inverse() }, camera.projection))
+    }
+
+    /// [returns] every light in the scene, with the world-space direction towards it.
+    pub fn world_lights(&self) -> Vec<(Vec3, Light)> {
+        zip_nodes(self).filter_map(|(node, world)| {
+            Some((world.isometry.rotation * Vec3::z(), node.light?))
+        }).collect()
+    }
+}
+
+/// [returns] every node of `scene` along with its world transform.
+fn zip_nodes(scene: &Scene) -> impl Iterator<Item = (&Node, na::Similarity3<f32>)> {
+    scene.nodes.iter().zip(scene.world_transforms())
+}
@@ -0,0 +1,224 @@
+use nalgebra as na;
+
+use vox_fwd::{Pt3, Vec3};
+
+use crate::model::{Mesh, Model};
+use crate::raster::{Fragment, Varying};
+
+/// What the shaders can see while a model is being drawn.
+pub struct Uniforms<'a> {
+    pub model: &'a Model,
+
+    // The mesh being drawn, which may be one of the model's levels of detail.
+    pub mesh: &'a Mesh,
+
+    pub model_view: na::Similarity3<f32>,
+    pub projection: na::Perspective3<f32>,
+
+    // The size of the surface, in pixels.
+    pub viewport: (usize, usize)
+}
+
+/// Places the vertices of a mesh in view space, and works out the varyings at the corners of its
+/// faces (which are interpolated across each face for the `FragmentShader`).
+pub trait VertexShader: Sync {
+    type Varying: Varying + Send + Sync;
+
+    /// [returns] the view-space position of vertex `v` of the mesh.
+    fn position(&self, uniforms: &Uniforms, v: usize) -> Pt3 {
+        uniforms.model_view.transform_point(&uniforms.mesh.vertices[v].vtx.0)
+    }
+
+    /// [returns] the varyings at a corner of `face`, where `view` holds the view-space positions
+    ///           of the face's corners.
+    ///
+    /// [note]    This is only called for faces that might be visible (i.e. not back-facing).
+    fn varying(&self, uniforms: &Uniforms, face: usize, corner: usize, view: &[Pt3; 3]) -> Self::Varying;
+}
+
+/// Colours the pixels of a face, from the varyings interpolated across it.
+pub trait FragmentShader<V>: Sync {
+    /// [returns] the colour of a pixel of `face`, or None to leave the pixel (and its depth) as is.
+    fn color(&self, uniforms: &Uniforms, face: usize, fragment: &Fragment<V>) -> Option<[u8; 3]>;
+}
+
+/// [returns] the view-space normal at a corner of `face`, which is the face's own normal for faces
+///           without normals.
+///
+/// [note]    This isn't normalized (and neither are the interpolated normals in a fragment).
+pub fn corner_normal(uniforms: &Uniforms, face: usize, corner: usize, view: &[Pt3; 3]) -> Vec3 {
+    match uniforms.mesh.faces[face].normals {
+        Some(normals) => uniforms.model_view.transform_vector(&uniforms.mesh.normals[normals[corner]]),
+        None => (view[1] - view[0]).cross(&(view[2] - view[0]))
+    }
+}
+
+/// [returns] the colour at a corner of `face` (from 0 to 1): the colour of the face if it has one,
+///           or else the colour of the corner's vertex if all three have one, or else white.
+pub fn corner_color(mesh: &Mesh, face: usize, corner: usize) -> Vec3 {
+    let face = &mesh.faces[face];
+    let vertex_colors = face.vertices.map(|v| mesh.vertices[v].color);
+
+    let color = face.color.or_else(|| vertex_colors.iter().all(Option::is_some).then(|| vertex_colors[corner].unwrap()));
+    color.map_or(Vec3::repeat(1.0), |c| Vec3::new(c[0] as f32, c[1] as f32, c[2] as f32) / 255.0)
+}
+
+/// [returns] how much of the texture (as an area in texture coordinates) a pixel covers at a corner
+///           of `face`, for `Texture2D::level_of_detail`, or zero if the face has no texture
+///           coordinates.
+///
+/// [note]    This is worked out over the whole face, then scaled by the distance to each corner,
+///           since further parts of the face are squeezed into fewer pixels.
+pub fn corner_uv_area(uniforms: &Uniforms, face: usize, corner: usize, view: &[Pt3; 3]) -> f32 {
+    let Some(uvs) = uniforms.mesh.faces[face].uvs else { return 0.0 };
+    let [t0, t1, t2] = uvs.map(|i| uniforms.mesh.uvs[i]);
+    let uv_area = (t1 - t0).perp(&(t2 - t0)).abs() / 2.0;
+
+    // Behind the camera, the area on screen means nothing (and the face will be clipped anyway).
+    let depths = view.map(|p| -p.z);
+    if depths.iter().any(|&d| d <= 0.0) {
+        return 0.0;
+    }
+
+    let half = na::Vector2::new(uniforms.viewport.0 as f32, uniforms.viewport.1 as f32) / 2.0;
+    let [s0, s1, s2] = view.map(|p| uniforms.projection.project_point(&p).xy().coords.component_mul(&half));
+    let screen_area = (s1 - s0).perp(&(s2 - s0)).abs() / 2.0;
+    if screen_area <= 0.0 {
+        return 0.0;
+    }
+
+    let mean_depth = depths.iter().product::<f32>().cbrt();
+    uv_area / screen_area * (depths[corner] / mean_depth).powi(2)
+}
+
+fn scale_color(color: [u8; 3], by: &Vec3) -> [u8; 3] {
+    [0, 1, 2].map(|i| (color[i] as f32 * by[i].clamp(0.0, 1.0)) as u8)
+}
+
+/// The normal, the texture coordinates along with the area of texture per pixel (see
+/// `corner_uv_area`), and the tint.
+pub type MatcapVarying = (Vec3, (na::Vector2<f32>, f32), Vec3);
+
+/// [returns] `tint` multiplied by the model's base-colour texture at `uv`, if it has one and `face`
+///           has texture coordinates.
+fn textured_tint(uniforms: &Uniforms, face: usize, uv: &na::Vector2<f32>, uv_area: f32, tint: &Vec3) -> Vec3 {
+    match &uniforms.model.base_color {
+        Some(texture) if uniforms.mesh.faces[face].uvs.is_some() => {
+            texture.sample(&(*uv).into(), texture.level_of_detail(uv_area)).xyz().component_mul(tint)
+        }
+        _ => *tint
+    }
+}
+
+/// Shades with the model's matcap texture, tinted by the colours of the faces (or vertices) and
+/// by the base-colour texture, if the model has one. This is what `grr::render_model` uses.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct MatcapShader;
+
+impl VertexShader for MatcapShader {
+    type Varying = MatcapVarying;
+
+    fn varying(&self, uniforms: &Uniforms, face: usize, corner: usize, view: &[Pt3; 3]) -> Self::Varying {
+        let uv = uniforms.mesh.faces[face].uvs.map_or(na::Vector2::zeros(), |uvs| uniforms.mesh.uvs[uvs[corner]].coords);
+        let uv_area = corner_uv_area(uniforms, face, corner, view);
+        (corner_normal(uniforms, face, corner, view), (uv, uv_area), corner_color(uniforms.mesh, face, corner))
+    }
+}
+
+impl FragmentShader<MatcapVarying> for MatcapShader {
+    fn color(&self, uniforms: &Uniforms, face: usize, fragment: &Fragment<MatcapVarying>) -> Option<[u8; 3]> {
+        let (normal, (uv, uv_area), tint) = &fragment.varying;
+
+        // Interpolated normals come out a little short of unit length.
+        let normal = normal.try_normalize(0.0).unwrap_or(*normal);
+        let color = uniforms.model.texture.sample_normal(&normal).0;
+
+        let tint = textured_tint(uniforms, face, uv, *uv_area, tint);
+
+        Some(scale_color(color, &tint))
+    }
+}
+
+/// Shades with diffuse light from a single direction, on top of some ambient light.
+///
+/// see: https://en.wikipedia.org/wiki/Lambertian_reflectance
+///
+#[derive(Copy, Clone, Debug)]
+pub struct LambertShader {
+    /// The direction towards the light, in view space.
+    pub light: Vec3,
+
+    /// How bright faces turned away from the light are (from 0 to 1).
+    pub ambient: f32,
+
+    pub color: [u8; 3]
+}
+
+impl Default for LambertShader {
+    /// A light over the viewer's shoulder.
+    fn default() -> Self {
+        Self { light: Vec3::new(0.3, 0.5, 1.0).normalize(), ambient: 0.2, color: [255; 3] }
+    }
+}
+
+impl VertexShader for LambertShader {
+    // The normal and the tint.
+    type Varying = (Vec3, Vec3);
+
+    fn varying(&self, uniforms: &Uniforms, face: usize, corner: usize, view: &[Pt3; 3]) -> Self::Varying {
+        (corner_normal(uniforms, face, corner, view), corner_color(uniforms.mesh, face, corner))
+    }
+}
+
+impl FragmentShader<(Vec3, Vec3)> for LambertShader {
+    fn color(&self, _: &Uniforms, _: usize, fragment: &Fragment<(Vec3, Vec3)>) -> Option<[u8; 3]> {
+        let (normal, tint) = &fragment.varying;
+
+        let diffuse = normal.try_normalize(0.0).map_or(0.0, |n| n.dot(&self.light).max(0.0));
+        let brightness = self.ambient + (1.0 - self.ambient) * diffuse;
+
+        Some(scale_color(self.color, &(tint * brightness)))
+    }
+}
+
+/// A light shining from one direction (i.e. the sun), for the `LitShader`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct DirectionalLight {
+    /// The direction towards the light, in view space.
+    pub direction: Vec3,
+
+    /// The brightness of each channel (where 1 lights a face facing the light fully).
+    pub color: Vec3
+}
+
+/// Shades with diffuse light from any number of `DirectionalLight`s, on top of some ambient light,
+/// tinted like the `MatcapShader` is (by the colours of the faces and the base-colour texture).
+#[derive(Clone, Debug, Default)]
+pub struct LitShader {
+    pub lights: Vec<DirectionalLight>,
+    pub ambient: f32
+}
+
+impl VertexShader for LitShader {
+    type Varying = MatcapVarying;
+
+    fn varying(&self, uniforms: &Uniforms, face: usize, corner: usize, view: &[Pt3; 3]) -> Self::Varying {
+        MatcapShader.varying(uniforms, face, corner, view)
+    }
+}
+
+impl FragmentShader<MatcapVarying> for LitShader {
+    fn color(&self, uniforms: &Uniforms, face: usize, fragment: &Fragment<MatcapVarying>) -> Option<[u8; 3]> {
+        let (normal, (uv, uv_area), tint) = &fragment.varying;
+
+        let tint = textured_tint(uniforms, face, uv, *uv_area, tint);
+
+        let normal = normal.try_normalize(0.0);
+        let light = self.lights.iter().fold(Vec3::repeat(self.ambient), |sum, light| {
+            let diffuse = normal.map_or(0.0, |n| n.dot(&light.direction.normalize()).max(0.0));
+            sum + light.color * diffuse
+        });
+
+        Some(scale_color([255; 3], &tint.component_mul(&light)))
+    }
+}
@@ -0,0 +1,178 @@
+use std::collections::BTreeMap;
+
+use vox_fwd::{Pt3, Vec3};
+
+use crate::halfedge::HalfEdgeMesh;
+use crate::model::Mesh;
+
+/// How `Mesh::smooth` moves each vertex towards the average of its neighbours.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Smoothing {
+    /// Every neighbour counts the same. Cheap, but it also evens out the size of the faces, and
+    /// shrinks the mesh a little with every iteration.
+    Laplacian { lambda: f32 },
+
+    /// Neighbours count by the cotangents of the angles opposite their edges, which moves vertices
+    /// across the surface less (so the faces keep their shape), but still shrinks the mesh.
+    Cotangent { lambda: f32 },
+
+    /// A Laplacian step by `lambda` followed by one by `mu` (which is negative, and a little larger
+    /// than `lambda`), which undoes the shrinking while still removing noise. Something like
+    /// `lambda: 0.5, mu: -0.53` works well.
+    ///
+    /// see: Taubin, "A signal processing approach to fair surface design" (1995)
+    ///
+    Taubin { lambda: f32, mu: f32 }
+}
+
+/// Which vertices `Mesh::smooth` leaves where they are.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Pinning {
+    /// Pins the vertices on the edges of holes and open surfaces.
+    pub boundary: bool,
+
+    /// Pins the vertices on edges where the faces meet at more than this angle (in radians), so
+    /// that the sharp edges of a part stay sharp.
+    pub feature_angle: Option<f32>
+}
+
+/// The neighbours of a vertex, with the weight of each.
+type Weights = Vec<Vec<(usize, f32)>>;
+
+impl Mesh {
+    /// [returns] the neighbours of every vertex, each with the same weight.
+    fn uniform_weights(&self) -> Weights {
+        let mut neighbours : Weights = vec![vec![]; self.vertices.len()];
+
+        for face in &self.faces {
+            let [a, b, c] = face.vertices;
+            for (u, v) in [(a, b), (b, c), (c, a)] {
+                if u != v {
+                    neighbours[u].push((v, 1.0));
+                    neighbours[v].push((u, 1.0));
+                }
+            }
+        }
+
+        // Shared edges were added once by each face.
+        for n in &mut neighbours {
+            n.sort_unstable_by_key(|&(v, _)| v);
+            n.dedup_by_key(|&mut (v, _)| v);
+        }
+
+        neighbours
+    }
+
+    /// [returns] the neighbours of every vertex, weighted by (half) the sum of the cotangents of
+    ///           the angles opposite their edge.
+    ///
+    /// [note]    Obtuse triangles can make a weight negative, which is clamped to zero to keep the
+    ///           smoothing stable.
+    fn cotangent_weights(&self) -> Weights {
+        let mut edges : BTreeMap<(usize, usize), f32> = BTreeMap::new();
+
+        for face in &self.faces {
+            for corner in 0..3 {
+                let [k, i, j] = [0, 1, 2].map(|o| face.vertices[(corner + o) % 3]);
+                if i == j {
+                    continue;
+                }
+
+                // The angle at k, opposite the edge (i, j).
+                let (pk, pi, pj) = (self.vertices[k].vtx.0, self.vertices[i].vtx.0, self.vertices[j].vtx.0);
+                let (e1, e2) = (pi - pk, pj - pk);
+                let sin = e1.cross(&e2).norm();
+                let cot = if sin > 0.0 { e1.dot(&e2) / sin } else { 0.0 };
+
+                *edges.entry((i.min(j), i.max(j))).or_default() += cot / 2.0;
+            }
+        }
+
+        let mut neighbours : Weights = vec![vec![]; self.vertices.len()];
+        for ((i, j), w) in edges {
+            neighbours[i].push((j, w.max(0.0)));
+            neighbours[j].push((i, w.max(0.0)));
+        }
+        neighbours
+    }
+
+    /// [returns] which vertices `pinning` keeps in place.
+    fn pinned(&self, pinning: &Pinning) -> Vec<bool> {
+        let mut pinned = vec![false; self.vertices.len()];
+        if !pinning.boundary && pinning.feature_angle.is_none() {
+            return pinned;
+        }
+
+        let half_edges = HalfEdgeMesh::from_mesh(self);
+        let face_normal = |f: usize| {
+            let [p0, p1, p2] = half_edges.face_vertices(f).map(|v| self.vertices[v].vtx.0);
+            (p1 - p0).cross(&(p2 - p0)).try_normalize(0.0).unwrap_or_else(Vec3::zeros)
+        };
+
+        for h in half_edges.edges() {
+            let half_edge = &half_edges.half_edges[h];
+
+            let pin = match half_edge.twin {
+                None => pinning.boundary,
+                Some(twin) => pinning.feature_angle.is_some_and(|angle| {
+                    face_normal(half_edge.face).angle(&face_normal(half_edges.half_edges[twin].face)) > angle
+                })
+            };
+
+            if pin {
+                pinned[half_edge.origin] = true;
+                pinned[half_edges.destination(h)] = true;
+            }
+        }
+
+        pinned
+    }
+
+    /// Moves every vertex (that isn't pinned) by `factor` of the way to the weighted average of its
+    /// neighbours.
+    fn smoothing_step(&mut self, weights: &Weights, pinned: &[bool], factor: f32) {
+        let positions : Vec<Pt3> = self.vertices.iter().map(|v| v.vtx.0).collect();
+
+        for (v, vertex) in self.vertices.iter_mut().enumerate() {
+            let total : f32 = weights[v].iter().map(|&(_, w)| w).sum();
+            if pinned[v] || total <= 0.0 {
+                continue;
+            }
+
+            let average = weights[v].iter().map(|&(u, w)| positions[u].coords * w).sum::<Vec3>() / total;
+            vertex.vtx.0 += (average - positions[v].coords) * factor;
+        }
+    }
+
+    /// Smooths out noise (i.e. from a scan) by moving each vertex towards its neighbours,
+    /// `iterations` times over.
+    ///
+    /// Neighbours are the vertices that share an edge, so faces need to share their vertices (as
+    /// they do from `from_facets`, or after `weld`). Vertices that aren't used by any faces, and
+    /// those picked out by `pinning`, are left alone.
+    ///
+    /// [note] Normals and other attributes are left as they were, so normals worked out from the
+    ///        faces are best recomputed.
+    ///
+    pub fn smooth(&mut self, method: Smoothing, iterations: usize, pinning: &Pinning) {
+        let pinned = self.pinned(pinning);
+        let uniform = self.uniform_weights();
+
+        for _ in 0..iterations {
+            match method {
+                Smoothing::Laplacian { lambda } => {
+                    self.smoothing_step(&uniform, &pinned, lambda);
+                }
+                Smoothing::Cotangent { lambda } => {
+                    // The angles change as the vertices move.
+                    let weights = self.cotangent_weights();
+                    self.smoothing_step(&weights, &pinned, lambda);
+                }
+                Smoothing::Taubin { lambda, mu } => {
+                    self.smoothing_step(&uniform, &pinned, lambda);
+                    self.smoothing_step(&uniform, &pinned, mu);
+                }
+            }
+        }
+    }
+}
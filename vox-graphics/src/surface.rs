@@ -2,7 +2,7 @@ use image::{ImageBuffer, RgbImage, Rgb, DynamicImage};
 use image::imageops::FilterType;
 
 use vox_fwd::{Pt3};
-use crate::raster::Pixel;
+use crate::raster::{Pixel, Region};
 
 
 /// [brief] This struct represents a simple rendering surface.
@@ -93,6 +93,35 @@ impl Surface {
         x + (self.shape.0 * y)
     }
 
+    /// [returns] copies of the surface in tiles of `size` by `size` pixels (smaller along the right
+    ///           and bottom edges), in rows from the top left.
+    pub fn tiles(&self, size: usize) -> Vec<Tile> {
+        let (width, height) = self.shape;
+
+        (0..height).step_by(size).flat_map(|y0| (0..width).step_by(size).map(move |x0| {
+            (x0..(x0 + size).min(width), y0..(y0 + size).min(height))
+        })).map(|region| {
+            let rows = region.1.clone().map(|y| self.get_index(region.0.start, y)..self.get_index(region.0.end, y));
+
+            Tile {
+                z_buffer: rows.clone().flat_map(|row| self.z_buffer[row].iter().copied()).collect(),
+                p_buffer: rows.flat_map(|row| self.p_buffer[row].iter().copied()).collect(),
+                region
+            }
+        }).collect()
+    }
+
+    /// Copies `tile` (from `tiles`) back into the surface.
+    pub fn put_tile(&mut self, tile: &Tile) {
+        let width = tile.region.0.len();
+
+        for (j, y) in tile.region.1.clone().enumerate() {
+            let row = self.get_index(tile.region.0.start, y)..self.get_index(tile.region.0.end, y);
+            self.z_buffer[row.clone()].copy_from_slice(&tile.z_buffer[j * width..(j + 1) * width]);
+            self.p_buffer[row].copy_from_slice(&tile.p_buffer[j * width..(j + 1) * width]);
+        }
+    }
+
     pub fn to_pixel(&self, p: &Pt3) -> (Pixel, f32) {
         (
             Pixel {
@@ -103,3 +132,38 @@ impl Surface {
         )
     }
 }
+
+/// A copy of a rectangle of a `Surface`, with its own z-buffer, so that the tiles of a surface can
+/// be drawn into in parallel (see `Surface::tiles`).
+///
+/// [note] Pixels are addressed by their position on the surface, not within the tile.
+pub struct Tile {
+    pub region: Region,
+    z_buffer: Vec<f32>,
+    p_buffer: Vec<[u8; 3]>
+}
+
+impl Tile {
+    pub fn get_z(&self, x: usize, y: usize) -> f32 {
+        self.z_buffer[self.get_index(x, y)]
+    }
+
+    pub fn set_z(&mut self, x: usize, y: usize, z: f32) {
+        let i = self.get_index(x, y);
+        self.z_buffer[i] = z;
+    }
+
+    pub fn set_pixel(&mut self, x: usize, y: usize, col: &[u8; 3]) {
+        let i = self.get_index(x, y);
+        self.p_buffer[i] = *col;
+    }
+
+    pub fn get_pixel(&self, x: usize, y: usize) -> &[u8; 3] {
+        &self.p_buffer[self.get_index(x, y)]
+    }
+
+    fn get_index(&self, x: usize, y: usize) -> usize {
+        debug_assert!(self.region.0.contains(&x) && self.region.1.contains(&y));
+        (x - self.region.0.start) + self.region.0.len() * (y - self.region.1.start)
+    }
+}
@@ -1,6 +1,8 @@
 use std::fs::File;
 use std::path::Path;
-use image::{Rgb, RgbImage};
+use image::{Rgb, Rgba, RgbImage, RgbaImage};
+
+use vox_fwd::Pt2;
 
 extern crate nalgebra as na;
 
@@ -30,3 +32,161 @@ impl MatcapTexture {
         *self.contents.get_pixel(uv.0 as u32, uv.1 as u32)
     }
 }
+
+/// What a `Texture2D` does with texture coordinates outside [0, 1].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Wrap {
+    #[default]
+    Repeat,
+
+    /// Stretches the texels along the edges.
+    Clamp,
+
+    /// Repeats the texture, flipping every other copy.
+    Mirror
+}
+
+impl Wrap {
+    /// [returns] the texel that texel `i` of a row (or column) of `len` texels stands for.
+    fn texel(&self, i: i64, len: u32) -> u32 {
+        let len = len as i64;
+        let i = match self {
+            Wrap::Repeat => i.rem_euclid(len),
+            Wrap::Clamp => i.clamp(0, len - 1),
+            Wrap::Mirror => {
+                let i = i.rem_euclid(2 * len);
+                if i < len { i } else { 2 * len - 1 - i }
+            }
+        };
+        i as u32
+    }
+}
+
+/// How a `Texture2D` blends texels.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Filter {
+    /// The nearest texel of the full-size image (which keeps pixel art crisp, but shimmers once
+    /// the texture is smaller on screen than it is).
+    Nearest,
+
+    /// A blend of the four nearest texels, from the mipmap nearest to the level of detail.
+    Bilinear,
+
+    /// A bilinear sample from each of the two mipmaps around the level of detail, blended.
+    #[default]
+    Trilinear
+}
+
+/// A texture sampled by texture coordinates, with its mipmaps.
+#[derive(Clone)]
+pub struct Texture2D {
+    // The image, then each mipmap at half the size of the one before, down to 1x1.
+    levels: Vec<RgbaImage>,
+
+    pub wrap: Wrap,
+    pub filter: Filter
+}
+
+/// [returns] `image` at half the size (rounding down, but no smaller than 1x1), with each texel the
+///           average of the 2x2 texels it covers.
+fn half_size(image: &RgbaImage) -> RgbaImage {
+    let (width, height) = image.dimensions();
+
+    RgbaImage::from_fn((width / 2).max(1), (height / 2).max(1), |x, y| {
+        let mut sum = [0u32; 4];
+        for (dx, dy) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+            let px = image.get_pixel((2 * x + dx).min(width - 1), (2 * y + dy).min(height - 1));
+            for (s, c) in sum.iter_mut().zip(px.0) {
+                *s += c as u32;
+            }
+        }
+        Rgba(sum.map(|s| ((s + 2) / 4) as u8))
+    })
+}
+
+impl Texture2D {
+    /// [returns] a texture of `image` (which must have at least one texel), repeating and with
+    ///           trilinear filtering.
+    pub fn new(image: RgbaImage) -> Self {
+        assert!(image.width() > 0 && image.height() > 0, "textures can't be empty");
+
+        let mut levels = vec![image];
+        while let Some(last) = levels.last().filter(|l| l.width() > 1 || l.height() > 1) {
+            levels.push(half_size(last));
+        }
+
+        Self { levels, wrap: Wrap::default(), filter: Filter::default() }
+    }
+
+    /// [returns] the width and height of the full-size image.
+    pub fn dimensions(&self) -> (u32, u32) {
+        self.levels[0].dimensions()
+    }
+
+    /// [returns] the image and its mipmaps, from the full size down to 1x1.
+    pub fn levels(&self) -> &[RgbaImage] {
+        &self.levels
+    }
+
+    /// [returns] the level of detail (the mipmap, as a fraction) for when each pixel on screen covers
+    ///           an area of `uv_area` in texture coordinates.
+    pub fn level_of_detail(&self, uv_area: f32) -> f32 {
+        // Each pixel covers sqrt(texels) texels across.
+        let (width, height) = self.dimensions();
+        let texels = uv_area * width as f32 * height as f32;
+        0.5 * texels.log2()
+    }
+
+    /// [returns] the colour (with each channel from 0 to 1) at `uv` (with v pointing up), at the
+    ///           level of detail `lod` (see `level_of_detail`).
+    ///
+    /// [note]    Levels of detail below zero are magnified from the full-size image.
+    pub fn sample(&self, uv: &Pt2, lod: f32) -> na::Vector4<f32> {
+        let last = (self.levels.len() - 1) as f32;
+        let lod = if lod.is_nan() { 0.0 } else { lod.clamp(0.0, last) };
+
+        match self.filter {
+            Filter::Nearest => self.nearest(0, uv),
+            Filter::Bilinear => self.bilinear(lod.round() as usize, uv),
+            Filter::Trilinear => {
+                let (level, t) = (lod.floor(), lod.fract());
+                let below = self.bilinear(level as usize, uv);
+                if t == 0.0 {
+                    return below;
+                }
+                below.lerp(&self.bilinear(level as usize + 1, uv), t)
+            }
+        }
+    }
+
+    /// [returns] texel (x, y) of mipmap `level`, wrapped into the image.
+    fn texel(&self, level: usize, x: i64, y: i64) -> na::Vector4<f32> {
+        let image = &self.levels[level];
+        let px = image.get_pixel(self.wrap.texel(x, image.width()), self.wrap.texel(y, image.height()));
+        na::Vector4::from(px.0.map(|c| c as f32 / 255.0))
+    }
+
+    /// [returns] where `uv` is in mipmap `level`, in texels from its top-left corner.
+    fn position(&self, level: usize, uv: &Pt2) -> (f32, f32) {
+        let image = &self.levels[level];
+        (uv.x * image.width() as f32, (1.0 - uv.y) * image.height() as f32)
+    }
+
+    fn nearest(&self, level: usize, uv: &Pt2) -> na::Vector4<f32> {
+        let (x, y) = self.position(level, uv);
+        self.texel(level, x.floor() as i64, y.floor() as i64)
+    }
+
+    fn bilinear(&self, level: usize, uv: &Pt2) -> na::Vector4<f32> {
+        // Texel centres are at half-texels.
+        let (x, y) = self.position(level, uv);
+        let (x, y) = (x - 0.5, y - 0.5);
+        let (x0, y0) = (x.floor(), y.floor());
+        let (tx, ty) = (x - x0, y - y0);
+        let (x0, y0) = (x0 as i64, y0 as i64);
+
+        let top = self.texel(level, x0, y0).lerp(&self.texel(level, x0 + 1, y0), tx);
+        let bottom = self.texel(level, x0, y0 + 1).lerp(&self.texel(level, x0 + 1, y0 + 1), tx);
+        top.lerp(&bottom, ty)
+    }
+}
@@ -0,0 +1,392 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use vox_obj::triangulate::triangulate_polygon;
+
+use crate::model::{FaceInfo, Mesh};
+
+/// The problems found in a mesh by `Mesh::validate`, with faces and vertices given by index.
+///
+/// Edges are given as (lower, higher) vertex index pairs.
+///
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ValidationReport {
+    /// Edges shared by more than two faces.
+    pub non_manifold_edges: Vec<[usize; 2]>,
+
+    /// The holes in the surface, as loops of vertices following the winding of the faces around
+    /// them. Holes that touch at a vertex are split apart there.
+    pub boundary_loops: Vec<Vec<usize>>,
+
+    /// Chains of edges with a single face that don't close into a loop (i.e. where the faces
+    /// around a hole are wound inconsistently), which can't be filled.
+    pub open_boundaries: Vec<Vec<usize>>,
+
+    /// Faces that repeat a vertex, or have (next to) no area.
+    pub degenerate_faces: Vec<usize>,
+
+    /// Faces with the same vertices as an earlier face (in any order).
+    pub duplicate_faces: Vec<usize>,
+
+    /// Edges whose two faces both run along them in the same direction (i.e. one of them is
+    /// wound the wrong way around).
+    pub inconsistent_edges: Vec<[usize; 2]>,
+
+    /// The faces of each connected piece of the mesh (faces are connected through shared vertices).
+    pub components: Vec<Vec<usize>>
+}
+
+impl ValidationReport {
+    /// [returns] true if the mesh is a closed, consistently wound, 2-manifold surface.
+    pub fn is_watertight(&self) -> bool {
+        self.non_manifold_edges.is_empty()
+            && self.boundary_loops.is_empty()
+            && self.open_boundaries.is_empty()
+            && self.degenerate_faces.is_empty()
+            && self.duplicate_faces.is_empty()
+            && self.inconsistent_edges.is_empty()
+    }
+}
+
+/// What `Mesh::repair` changed, along with the problems that it couldn't fix.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RepairReport {
+    pub degenerate_faces_removed: usize,
+    pub duplicate_faces_removed: usize,
+    pub faces_flipped: usize,
+    pub holes_filled: usize,
+    pub faces_added: usize,
+
+    /// The result of validating the repaired mesh.
+    pub remaining: ValidationReport
+}
+
+fn edge_key(a: usize, b: usize) -> [usize; 2] {
+    if a < b { [a, b] } else { [b, a] }
+}
+
+/// [returns] the directed edges of `face`, following its winding.
+fn directed_edges(face: &FaceInfo) -> [(usize, usize); 3] {
+    let [a, b, c] = face.vertices;
+    [(a, b), (b, c), (c, a)]
+}
+
+/// Swaps the winding of `face`, along with its per-corner attributes.
+fn flip(face: &mut FaceInfo) {
+    face.vertices.swap(1, 2);
+    if let Some(normals) = face.normals.as_mut() {
+        normals.swap(1, 2);
+    }
+    if let Some(uvs) = face.uvs.as_mut() {
+        uvs.swap(1, 2);
+    }
+}
+
+/// A union-find over face indices.
+struct Components(Vec<usize>);
+
+impl Components {
+    fn find(&mut self, i: usize) -> usize {
+        let mut root = i;
+        while self.0[root] != root {
+            root = self.0[root];
+        }
+        let mut i = i;
+        while self.0[i] != root {
+            let next = self.0[i];
+            self.0[i] = root;
+            i = next;
+        }
+        root
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.find(a), self.find(b));
+        self.0[a.max(b)] = a.min(b);
+    }
+}
+
+impl Mesh {
+    /// [returns] the faces on each (undirected) edge, in order.
+    ///
+    /// [note]    The collapsed edges of faces that repeat a vertex are left out.
+    fn edge_faces(&self) -> BTreeMap<[usize; 2], Vec<usize>> {
+        let mut edges : BTreeMap<[usize; 2], Vec<usize>> = BTreeMap::new();
+        for (face_i, face) in self.faces.iter().enumerate() {
+            for (a, b) in directed_edges(face).into_iter().filter(|(a, b)| a != b) {
+                edges.entry(edge_key(a, b)).or_default().push(face_i);
+            }
+        }
+        edges
+    }
+
+    /// [returns] true if the face has (next to) no area, relative to the length of its edges.
+    fn is_degenerate(&self, face: &FaceInfo) -> bool {
+        let [a, b, c] = face.vertices;
+        if a == b || b == c || c == a {
+            return true;
+        }
+
+        let [p0, p1, p2] = face.vertices.map(|v| self.vertices[v].vtx.0);
+        let (d1, d2) = (p1 - p0, p2 - p0);
+        let longest = d1.norm_squared().max(d2.norm_squared()).max((p2 - p1).norm_squared());
+
+        d1.cross(&d2).norm() <= f32::EPSILON * longest
+    }
+
+    /// [returns] the closed loops of edges that only have a single face, and the chains of them
+    ///           that don't close.
+    fn boundary_loops(&self, edges: &BTreeMap<[usize; 2], Vec<usize>>) -> (Vec<Vec<usize>>, Vec<Vec<usize>>) {
+        // The outgoing boundary half-edges from each vertex. There can be more than one where
+        // holes touch at a vertex.
+        let mut next : BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+        for (edge, faces) in edges {
+            if let [face_i] = faces[..] {
+                let (a, b) = directed_edges(&self.faces[face_i]).into_iter()
+                    .find(|&(a, b)| edge_key(a, b) == *edge)
+                    .unwrap();
+                next.entry(a).or_default().push(b);
+            }
+        }
+
+        let (mut loops, mut open) = (vec![], vec![]);
+
+        while let Some((&start, _)) = next.iter().find(|(_, outgoing)| !outgoing.is_empty()) {
+            let mut path = vec![start];
+            let mut on_path = HashMap::from([(start, 0)]);
+
+            // Follow the half-edges, splitting off a loop whenever the path comes back to a vertex
+            // it has already been through (which is how holes touching at a vertex are told apart).
+            while let Some(to) = next.get_mut(path.last().unwrap()).and_then(|outgoing| outgoing.pop()) {
+                match on_path.get(&to) {
+                    Some(&i) => {
+                        let closed = path.split_off(i + 1);
+                        for v in &closed {
+                            on_path.remove(v);
+                        }
+                        loops.push([vec![to], closed].concat());
+                    }
+                    None => {
+                        on_path.insert(to, path.len());
+                        path.push(to);
+                    }
+                }
+            }
+
+            // Getting stuck anywhere but the start leaves a chain that doesn't close.
+            if path.len() > 1 {
+                open.push(path);
+            }
+        }
+
+        (loops, open)
+    }
+
+    fn components(&self) -> Vec<Vec<usize>> {
+        let mut components = Components((0..self.faces.len()).collect());
+        let mut first_face_of_vertex = vec![usize::MAX; self.vertices.len()];
+
+        for (face_i, face) in self.faces.iter().enumerate() {
+            for v in face.vertices {
+                if first_face_of_vertex[v] == usize::MAX {
+                    first_face_of_vertex[v] = face_i;
+                } else {
+                    components.union(first_face_of_vertex[v], face_i);
+                }
+            }
+        }
+
+        let mut grouped : BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+        for face_i in 0..self.faces.len() {
+            grouped.entry(components.find(face_i)).or_default().push(face_i);
+        }
+        grouped.into_values().collect()
+    }
+
+    /// [returns] a report of everything that stops the mesh from being a clean, closed surface
+    ///           (which is what slicers expect).
+    pub fn validate(&self) -> ValidationReport {
+        let edges = self.edge_faces();
+
+        let (boundary_loops, open_boundaries) = self.boundary_loops(&edges);
+
+        let mut report = ValidationReport {
+            boundary_loops,
+            open_boundaries,
+            components: self.components(),
+            ..ValidationReport::default()
+        };
+
+        for (edge, faces) in &edges {
+            match faces[..] {
+                [f0, f1] => {
+                    let runs_along = |face_i: usize| directed_edges(&self.faces[face_i]).contains(&(edge[0], edge[1]));
+                    if runs_along(f0) == runs_along(f1) {
+                        report.inconsistent_edges.push(*edge);
+                    }
+                }
+                [_] => {}
+                _ => report.non_manifold_edges.push(*edge)
+            }
+        }
+
+        let mut seen = HashSet::new();
+        for (face_i, face) in self.faces.iter().enumerate() {
+            if self.is_degenerate(face) {
+                report.degenerate_faces.push(face_i);
+            }
+
+            let mut key = face.vertices;
+            key.sort_unstable();
+            if !seen.insert(key) {
+                report.duplicate_faces.push(face_i);
+            }
+        }
+
+        report
+    }
+
+    /// Rebuilds the list of faces of every vertex, after faces have been removed or added.
+    pub(crate) fn relink_faces(&mut self) {
+        self.vertices.iter_mut().for_each(|v| v.faces.clear());
+        for (face_i, face) in self.faces.iter().enumerate() {
+            for v in face.vertices {
+                self.vertices[v].faces.push(face_i);
+            }
+        }
+    }
+
+    /// Makes the winding of each component consistent (which way around is arbitrary).
+    fn unify_winding(&mut self) {
+        let edges = self.edge_faces();
+        let mut visited = vec![false; self.faces.len()];
+
+        // Walk across manifold edges, flipping each neighbour that disagrees with the face we came
+        // from. Faces that only touch the rest at a vertex (or a non-manifold edge) start walks of
+        // their own.
+        for seed in 0..self.faces.len() {
+            if visited[seed] {
+                continue;
+            }
+            visited[seed] = true;
+            let mut stack = vec![seed];
+
+            while let Some(face_i) = stack.pop() {
+                for (a, b) in directed_edges(&self.faces[face_i]) {
+                    let Some(&[f0, f1]) = edges.get(&edge_key(a, b)).map(|f| &f[..]) else { continue };
+                    let other = if f0 == face_i { f1 } else { f0 };
+
+                    if visited[other] {
+                        continue;
+                    }
+                    visited[other] = true;
+
+                    if directed_edges(&self.faces[other]).contains(&(a, b)) {
+                        flip(&mut self.faces[other]);
+                    }
+                    stack.push(other);
+                }
+            }
+        }
+    }
+
+    /// Turns each closed component outward, assuming its winding is consistent.
+    fn orient_outward(&mut self) {
+        let edges = self.edge_faces();
+
+        for component in self.components() {
+            let closed = component.iter().all(|&face_i| {
+                directed_edges(&self.faces[face_i]).iter().all(|&(a, b)| edges.get(&edge_key(a, b)).is_some_and(|f| f.len() == 2))
+            });
+
+            // A closed surface encloses positive volume when its faces point outward.
+            let signed_volume : f32 = component.iter().map(|&face_i| {
+                let [p0, p1, p2] = self.faces[face_i].vertices.map(|v| self.vertices[v].vtx.0.coords);
+                p0.dot(&p1.cross(&p2))
+            }).sum();
+
+            if closed && signed_volume < 0.0 {
+                for &face_i in &component {
+                    flip(&mut self.faces[face_i]);
+                }
+            }
+        }
+    }
+
+    /// Fixes what can be fixed without guessing at the intended shape:
+    ///     1. degenerate and duplicate faces are removed,
+    ///     2. the winding of each component is made consistent,
+    ///     3. holes with at most `max_hole_edges` edges are filled, and
+    ///     4. components that are (now) closed are turned outward.
+    ///
+    /// Non-manifold edges and large holes are left for the caller to deal with, and are listed in
+    /// the report. Vertices keep their indices, so unused ones are left in place.
+    ///
+    pub fn repair(&mut self, max_hole_edges: usize) -> RepairReport {
+        let before = self.validate();
+        let mut report = RepairReport::default();
+
+        let mut remove = vec![false; self.faces.len()];
+        for &face_i in &before.degenerate_faces {
+            remove[face_i] = true;
+            report.degenerate_faces_removed += 1;
+        }
+        for &face_i in &before.duplicate_faces {
+            if !remove[face_i] {
+                remove[face_i] = true;
+                report.duplicate_faces_removed += 1;
+            }
+        }
+
+        let mut face_i = 0;
+        self.faces.retain(|_| {
+            face_i += 1;
+            !remove[face_i - 1]
+        });
+        self.relink_faces();
+
+        // Flipping only ever swaps two corners, so a face that was flipped back and forth ends up
+        // exactly as it started.
+        let windings : Vec<[usize; 3]> = self.faces.iter().map(|f| f.vertices).collect();
+
+        self.unify_winding();
+
+        let edges = self.edge_faces();
+
+        // Only closed loops are filled, since a chain that doesn't close has no hole to fill.
+        let (boundary_loops, _) = self.boundary_loops(&edges);
+
+        for boundary in boundary_loops {
+            if boundary.len() < 3 || boundary.len() > max_hole_edges {
+                continue;
+            }
+
+            // The filling faces take the part of a face on the rim of the hole.
+            let part = edges[&edge_key(boundary[0], boundary[1])].first().map_or(0, |&f| self.faces[f].part);
+
+            // The faces around the hole run along its rim one way, so the filling runs the other.
+            let rim : Vec<usize> = boundary.iter().rev().copied().collect();
+            let corners : Vec<[f32; 3]> = rim.iter().map(|&v| self.vertices[v].vtx.0.into()).collect();
+
+            for tri in triangulate_polygon(&corners) {
+                self.faces.push(FaceInfo {
+                    vertices: tri.map(|i| rim[i]),
+                    color: None,
+                    part,
+                    normals: None,
+                    uvs: None
+                });
+                report.faces_added += 1;
+            }
+
+            report.holes_filled += 1;
+        }
+
+        self.orient_outward();
+        self.relink_faces();
+
+        report.faces_flipped = windings.iter().zip(&self.faces).filter(|(w, f)| **w != f.vertices).count();
+
+        report.remaining = self.validate();
+        report
+    }
+}
@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+
+use vox_stl::fwd::Facet;
+
+use crate::model::{Mesh, VertexInfo};
+
+/// What `Mesh::weld` changed.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct WeldReport {
+    /// The number of vertices that were merged into another (and so removed).
+    pub vertices_merged: usize,
+
+    /// The number of faces that were removed because welding collapsed two of their corners.
+    pub faces_collapsed: usize
+}
+
+/// The cell of the spatial hash that holds `p`.
+fn cell_of(p: &[f32; 3], cell_size: f32) -> [i64; 3] {
+    p.map(|c| (c / cell_size).floor() as i64)
+}
+
+impl Mesh {
+    /// Merges vertices that are within `epsilon` of each other, so that meshes exported with float
+    /// noise (where neighbouring faces don't quite share their corners) become connected.
+    ///
+    /// Vertices are bucketed into a spatial hash with cells `epsilon` wide, so only the 27 cells
+    /// around each vertex need to be searched. Vertices are merged into the first earlier surviving
+    /// vertex within reach (and take on its colour), so chains of close vertices don't all collapse
+    /// into one. An `epsilon` of zero merges only identical positions.
+    ///
+    /// Faces that end up with a repeated corner are removed, as are the merged vertices.
+    ///
+    pub fn weld(&mut self, epsilon: f32) -> WeldReport {
+        let mut report = WeldReport::default();
+
+        // The index of the vertex each one is merged into (possibly itself).
+        let mut target : Vec<usize> = (0..self.vertices.len()).collect();
+
+        if epsilon > 0.0 {
+            let mut cells : HashMap<[i64; 3], Vec<usize>> = HashMap::new();
+
+            for (i, vertex) in self.vertices.iter().enumerate() {
+                let p = vertex.vtx.0;
+                let [x, y, z] = cell_of(&[p.x, p.y, p.z], epsilon);
+
+                let neighbours = (-1..=1).flat_map(|dx| (-1..=1).flat_map(move |dy| (-1..=1).map(move |dz| [x + dx, y + dy, z + dz])));
+
+                let found = neighbours
+                    .filter_map(|cell| cells.get(&cell))
+                    .flatten()
+                    .filter(|&&j| (self.vertices[j].vtx.0 - p).norm() <= epsilon)
+                    .min();
+
+                match found {
+                    Some(&j) => target[i] = j,
+                    None => cells.entry([x, y, z]).or_default().push(i)
+                }
+            }
+        } else {
+            let mut exact : HashMap<[u32; 3], usize> = HashMap::new();
+
+            for (i, vertex) in self.vertices.iter().enumerate() {
+                let p = vertex.vtx.0;
+                // Adding 0.0 turns -0.0 into 0.0, so the two are welded together.
+                target[i] = *exact.entry([p.x + 0.0, p.y + 0.0, p.z + 0.0].map(f32::to_bits)).or_insert(i);
+            }
+        }
+
+        // Compact the surviving vertices.
+        let mut remap = vec![usize::MAX; self.vertices.len()];
+        let mut vertices : Vec<VertexInfo> = vec![];
+
+        for i in 0..self.vertices.len() {
+            if target[i] == i {
+                remap[i] = vertices.len();
+                vertices.push(VertexInfo{ vtx: self.vertices[i].vtx, faces: vec![], color: self.vertices[i].color });
+            } else {
+                report.vertices_merged += 1;
+            }
+        }
+
+        let faces_before = self.faces.len();
+
+        self.faces.retain_mut(|face| {
+            face.vertices = face.vertices.map(|v| remap[target[v]]);
+            let [a, b, c] = face.vertices;
+            a != b && b != c && c != a
+        });
+
+        report.faces_collapsed = faces_before - self.faces.len();
+
+        for (face_i, face) in self.faces.iter().enumerate() {
+            for v in face.vertices {
+                vertices[v].faces.push(face_i);
+            }
+        }
+
+        self.vertices = vertices;
+        report
+    }
+
+    /// Like `from_facets`, but also welds vertices that are within `epsilon` of each other (see
+    /// `weld`). The report doesn't count the identical vertices that `from_facets` always merges.
+    pub fn from_facets_welded<I: IntoIterator<Item = Facet>>(facets: I, epsilon: f32) -> (Self, WeldReport) {
+        let mut mesh = Self::from_facets(facets);
+        let report = mesh.weld(epsilon);
+        (mesh, report)
+    }
+}
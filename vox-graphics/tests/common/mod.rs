@@ -0,0 +1,42 @@
+// Fixtures shared by the tests (each test file only uses some of them).
+#![allow(dead_code)]
+
+use std::path::Path;
+use std::sync::Arc;
+
+use nalgebra as na;
+
+use vox_fwd::Pt3;
+use vox_graphics::clipping::BoundingSphere;
+use vox_graphics::model::{Mesh, Model};
+use vox_graphics::texture::MatcapTexture;
+use vox_stl::stl;
+
+/// [returns] the mesh of the STL file `name`, from the model resources.
+pub fn mesh(name: &str) -> Mesh {
+    Mesh::from_facets(stl::parse_from_file(Path::new(&format!("../resources/models/{}", name))).unwrap())
+}
+
+pub fn matcap() -> Arc<MatcapTexture> {
+    Arc::new(MatcapTexture::from_file(Path::new("../resources/matcaps/normal_256.png")))
+}
+
+/// [returns] the centre of the bounding sphere of `mesh`.
+pub fn center(mesh: &Mesh) -> Pt3 {
+    let points : Vec<Pt3> = mesh.vertices.iter().map(|v| v.vtx.0).collect();
+    BoundingSphere::from(&points).center
+}
+
+/// [returns] the sphere (of radius 0.5), centred on the origin.
+pub fn sphere() -> Model {
+    let mesh = mesh("ascii-sphere.stl");
+    let center = center(&mesh);
+
+    Model {
+        mesh: Arc::new(mesh),
+        transform: na::convert(na::Isometry3::translation(-center.x, -center.y, -center.z)),
+        texture: matcap(),
+        base_color: None,
+        lods: vec![]
+    }
+}
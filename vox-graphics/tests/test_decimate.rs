@@ -0,0 +1,92 @@
+use vox_fwd::Vec3;
+use vox_graphics::model::Mesh;
+use vox_stl::fwd::{Facet, Pt3};
+
+mod common;
+
+/// [returns] a flat, open grid of `n` by `n` squares in the z = 0 plane, from (0, 0) to (1, 1).
+fn grid(n: usize) -> Mesh {
+    let corner = |i: usize, j: usize| Pt3([i as f32 / n as f32, j as f32 / n as f32, 0.0]);
+    let facet = |tri| Facet { tri, normal: Pt3([0.0, 0.0, 1.0]), attribute: 0 };
+
+    Mesh::from_facets((0..n).flat_map(|i| (0..n).flat_map(move |j| [
+        facet([corner(i, j), corner(i + 1, j), corner(i + 1, j + 1)]),
+        facet([corner(i, j), corner(i + 1, j + 1), corner(i, j + 1)])
+    ])))
+}
+
+#[test]
+fn test_decimate_sphere() {
+    let mut sphere = common::mesh("ascii-sphere.stl");
+    let center = sphere.vertices.iter().map(|v| v.vtx.0.coords).sum::<Vec3>() / sphere.vertices.len() as f32;
+    let radius = (sphere.vertices[0].vtx.0.coords - center).norm();
+
+    let report = sphere.decimate(240, f32::INFINITY);
+
+    assert!(sphere.faces.len() <= 240 && sphere.faces.len() >= 200, "{}", sphere.faces.len());
+    assert_eq!(report.faces_removed, 960 - sphere.faces.len());
+    assert_eq!(report.vertices_removed, 482 - sphere.vertices.len());
+
+    // The surface is still closed, and still (roughly) a sphere.
+    let validation = sphere.validate();
+    assert!(validation.is_watertight(), "{:?}", validation);
+    assert_eq!(validation.components.len(), 1);
+
+    for v in &sphere.vertices {
+        assert!(((v.vtx.0.coords - center).norm() - radius).abs() < 0.05 * radius);
+    }
+
+    // Every vertex knows its faces.
+    for (face_i, face) in sphere.faces.iter().enumerate() {
+        assert!(face.vertices.iter().all(|&v| sphere.vertices[v].faces.contains(&face_i)));
+    }
+}
+
+#[test]
+fn test_decimate_error_bound() {
+    // [Scenario] every collapse would cut a corner off the cube
+    let mut cube = common::mesh("ascii-cube.stl");
+    let report = cube.decimate(0, 1e-3);
+    assert_eq!(report.faces_removed, 0);
+    assert_eq!(cube.faces.len(), 12);
+
+    // [Scenario] a flat grid can lose all of its inner vertices without any error
+    let mut flat = grid(8);
+    let report = flat.decimate(0, 1e-6);
+    assert!(report.max_error < 1e-6);
+    assert!(flat.faces.len() < 32, "{}", flat.faces.len());
+}
+
+#[test]
+fn test_decimate_keeps_boundary() {
+    // Moving the corners of the square costs far more than anything else, so they're left alone.
+    let mut flat = grid(10);
+    let report = flat.decimate(0, 1e-3);
+    assert!(flat.faces.len() < 20, "{}", flat.faces.len());
+    assert!(report.max_error < 1e-5);
+
+    // The outline of the grid is still the unit square, with a single boundary loop around it.
+    let validation = flat.validate();
+    assert_eq!(validation.boundary_loops.len(), 1);
+    assert!(validation.non_manifold_edges.is_empty());
+
+    for boundary_v in &validation.boundary_loops[0] {
+        let p = flat.vertices[*boundary_v].vtx.0;
+        let on_edge = |c: f32| c.abs() < 1e-5 || (c - 1.0).abs() < 1e-5;
+        assert!(on_edge(p.x) || on_edge(p.y), "{:?}", p);
+    }
+
+    for corner in [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]] {
+        assert!(flat.vertices.iter().any(|v| (v.vtx.0.xy() - nalgebra::Point2::from(corner)).norm() < 1e-5));
+    }
+
+    // No face was folded over, and the area is unchanged.
+    let mut area = 0.0;
+    for face in &flat.faces {
+        let [p0, p1, p2] = face.vertices.map(|v| flat.vertices[v].vtx.0);
+        let normal = (p1 - p0).cross(&(p2 - p0));
+        assert!(normal.z > 0.0);
+        area += normal.z / 2.0;
+    }
+    assert!((area - 1.0).abs() < 1e-4, "{}", area);
+}
@@ -133,3 +133,164 @@ fn test_scanlines_with_attributes() {
         assert!(validate_scanline_monotonicity(&scanlines[..]));
     }
 }
+
+#[test]
+fn test_rasterize_perspective_correct() {
+    use nalgebra as na;
+    use vox_graphics::raster;
+
+    // A triangle whose corners are at very different depths (w is the distance from the camera).
+    let clip = [
+        na::Vector4::new(-1.0, -1.0, 0.0, 1.0),
+        na::Vector4::new(8.0, -8.0, 0.0, 8.0),
+        na::Vector4::new(-2.0, 2.0, 0.0, 2.0)
+    ];
+
+    // [Scenario] interpolating w itself gives back the depth of each fragment
+    let mut fragments = 0;
+    raster::rasterize((64, 64), &clip, &clip.map(|c| c.w), |fragment| {
+        assert!((fragment.varying - 1.0 / fragment.depth).abs() < 1e-4 * fragment.varying);
+        fragments += 1;
+    });
+    assert!(fragments > 500, "{}", fragments);
+
+    // [Scenario] the texture coordinates at the middle of the far edge are halfway between its
+    //            corners in 3D, which is much closer to the near corner on screen
+    let uvs = [na::Vector2::new(0.0, 0.0), na::Vector2::new(1.0, 0.0), na::Vector2::new(0.0, 1.0)];
+    let mut halfway = vec![];
+    raster::rasterize((64, 64), &clip, &uvs, |fragment| {
+        if (fragment.varying.x - 0.5).abs() < 0.02 && fragment.varying.y < 0.02 {
+            halfway.push(fragment.x);
+        }
+    });
+
+    // Screen x runs from 0 at the first corner to 64 at the second (which is 8 times as far).
+    assert!(!halfway.is_empty());
+    assert!(halfway.iter().all(|&x| x > 50), "{:?}", halfway);
+
+    // [Scenario] tuples of varyings are interpolated together
+    let mut seen = false;
+    raster::rasterize((16, 16), &clip, &[(1.0f32, uvs[0]), (1.0, uvs[1]), (1.0, uvs[2])], |fragment| {
+        assert!((fragment.varying.0 - 1.0).abs() < 1e-5);
+        seen = true;
+    });
+    assert!(seen);
+}
+
+#[test]
+fn test_rasterize_coverage() {
+    use nalgebra as na;
+    use vox_graphics::raster;
+
+    // Two triangles covering the whole screen cover every pixel, whichever way they are wound.
+    let corner = |x: f32, y: f32| na::Vector4::new(x, y, 0.0, 1.0);
+    let mut covered = vec![0; 20 * 10];
+
+    for tri in [
+        [corner(-1.0, -1.0), corner(1.0, -1.0), corner(1.0, 1.0)],
+        [corner(-1.0, -1.0), corner(-1.0, 1.0), corner(1.0, 1.0)]
+    ] {
+        raster::rasterize((20, 10), &tri, &[(); 3], |fragment| {
+            assert!((fragment.depth - 1.0).abs() < 1e-6);
+            covered[fragment.x + 20 * fragment.y] += 1;
+        });
+    }
+
+    assert!(covered.iter().all(|&n| n >= 1));
+
+    // Only pixels on the shared diagonal are covered twice.
+    assert!(covered.iter().filter(|&&n| n == 2).count() <= 20);
+
+    // [Scenario] a triangle that's partly off screen is cut to the screen
+    let mut count = 0;
+    raster::rasterize((20, 10), &[corner(-3.0, -3.0), corner(3.0, -3.0), corner(0.0, 3.0)], &[(); 3], |fragment| {
+        assert!(fragment.x < 20 && fragment.y < 10);
+        count += 1;
+    });
+    assert!(count > 0);
+
+    // [Scenario] a degenerate triangle covers nothing
+    raster::rasterize((20, 10), &[corner(0.0, 0.0), corner(0.5, 0.5), corner(1.0, 1.0)], &[(); 3], |_| panic!());
+}
+
+#[test]
+fn test_surface_tiles() {
+    use vox_graphics::surface::Surface;
+
+    let mut surface = Surface::new(150, 70, false);
+    surface.set_pixel(149, 69, &[1, 2, 3]);
+    surface.set_z(64, 0, 0.5);
+
+    // [Scenario] the tiles cover every pixel once, with smaller tiles along the right and bottom
+    let mut tiles = surface.tiles(64);
+    assert_eq!(tiles.len(), 3 * 2);
+    assert_eq!(tiles[2].region, (128..150, 0..64));
+    assert_eq!(tiles[5].region, (128..150, 64..70));
+    assert_eq!(tiles.iter().map(|t| t.region.0.len() * t.region.1.len()).sum::<usize>(), 150 * 70);
+
+    // [Scenario] tiles start out as copies of the surface, and are copied back by put_tile
+    assert_eq!(tiles[5].get_pixel(149, 69), &[1, 2, 3]);
+    assert_eq!(tiles[1].get_z(64, 0), 0.5);
+
+    tiles[4].set_pixel(100, 69, &[4, 5, 6]);
+    tiles[4].set_z(100, 69, 0.25);
+    surface.put_tile(&tiles[4]);
+    assert_eq!(surface.get_pixel(100, 69), &[4, 5, 6]);
+    assert_eq!(surface.get_z(100, 69), 0.25);
+    assert_eq!(surface.get_pixel(149, 69), &[1, 2, 3]);
+}
+
+#[test]
+fn test_render_tris_tiled_matches_serial() {
+    use nalgebra as na;
+    use vox_graphics::surface::Surface;
+
+    // A small linear congruential generator, to get the same triangles every time.
+    let mut seed = 12345u64;
+    let mut random = move || {
+        seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        (seed >> 33) as f32 / (1u64 << 31) as f32
+    };
+
+    // Triangles of all sizes, at all depths, some partly off screen and some repeated.
+    let mut triangles : Vec<([na::Vector4<f32>; 3], [f32; 3])> = (0..300).map(|_| {
+        let (cx, cy, size) = (random() * 2.4 - 1.2, random() * 2.4 - 1.2, random().powi(3) * 2.0);
+        let clip = [0, 1, 2].map(|_| {
+            let w = 1.0 + random() * 10.0;
+            na::Vector4::new((cx + (random() - 0.5) * size) * w, (cy + (random() - 0.5) * size) * w, 0.0, w)
+        });
+        (clip, [random(), random(), random()])
+    }).collect();
+    let repeats = triangles[..20].iter().map(|(clip, _)| (*clip, [0.0; 3])).collect::<Vec<_>>();
+    triangles.extend(repeats);
+
+    let shade = |fragment: &vox_graphics::raster::Fragment<f32>| {
+        (fragment.varying < 0.9).then_some([(fragment.varying * 255.0) as u8, (fragment.depth * 255.0) as u8, 7])
+    };
+
+    for (width, height) in [(150, 90), (64, 64), (7, 200)] {
+        // [Scenario] both start from the same picture, and draw the same triangles over it
+        let mut serial = Surface::new(width, height, false);
+        for (clip, varyings) in &triangles[..50] {
+            grr::render_tri_interpolated(&mut serial, clip, varyings, shade);
+        }
+
+        let mut tiled = Surface::new(width, height, false);
+        grr::render_tris_tiled(&mut tiled, &triangles[..50], |_, fragment| shade(fragment));
+        assert_eq!(serial.to_img(), tiled.to_img());
+
+        for (clip, varyings) in &triangles[50..] {
+            grr::render_tri_interpolated(&mut serial, clip, varyings, shade);
+        }
+        grr::render_tris_tiled(&mut tiled, &triangles[50..], |_, fragment| shade(fragment));
+
+        let drawn = serial.to_img().pixels().filter(|p| p.0 != [0; 3]).count();
+        assert!(drawn > width * height / 4, "{}", drawn);
+        assert_eq!(serial.to_img(), tiled.to_img());
+        for y in 0..height {
+            for x in 0..width {
+                assert_eq!(serial.get_z(x, y), tiled.get_z(x, y));
+            }
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use vox_graphics::halfedge::HalfEdgeMesh;
+use vox_graphics::model::Mesh;
+
+mod common;
+
+/// [returns] the vertices that share an edge with `v`, worked out directly from the faces.
+fn brute_neighbours(mesh: &Mesh, v: usize) -> BTreeSet<usize> {
+    mesh.faces.iter()
+        .filter(|f| f.vertices.contains(&v))
+        .flat_map(|f| f.vertices)
+        .filter(|&u| u != v)
+        .collect()
+}
+
+/// Checks that `a` and `b` have the same faces, vertices and attributes.
+fn assert_same(a: &Mesh, b: &Mesh) {
+    assert_eq!(a.faces.len(), b.faces.len());
+    for (fa, fb) in a.faces.iter().zip(&b.faces) {
+        assert_eq!((fa.vertices, fa.color, fa.part, fa.normals, fa.uvs), (fb.vertices, fb.color, fb.part, fb.normals, fb.uvs));
+    }
+
+    assert_eq!(a.vertices.len(), b.vertices.len());
+    for (va, vb) in a.vertices.iter().zip(&b.vertices) {
+        assert_eq!((va.vtx.0, va.color), (vb.vtx.0, vb.color));
+        assert_eq!(va.faces.iter().collect::<BTreeSet<_>>(), vb.faces.iter().collect::<BTreeSet<_>>());
+    }
+
+    assert_eq!((&a.parts, &a.normals, &a.uvs), (&b.parts, &b.normals, &b.uvs));
+}
+
+#[test]
+fn test_halfedge_closed_mesh() {
+    for name in ["ascii-cube.stl", "ascii-sphere.stl"] {
+        let mesh = common::mesh(name);
+        let he = HalfEdgeMesh::from_mesh(&mesh);
+
+        assert_eq!(he.half_edges.len(), 3 * mesh.faces.len());
+        assert!(he.half_edges.iter().enumerate().all(|(h, e)| e.twin.is_some_and(|t| he.half_edges[t].twin == Some(h))));
+        assert_eq!(he.edges().count(), 3 * mesh.faces.len() / 2);
+
+        // Euler characteristic of a sphere.
+        assert_eq!(mesh.vertices.len() + mesh.faces.len() - he.edges().count(), 2);
+
+        for f in 0..mesh.faces.len() {
+            assert_eq!(he.face_vertices(f), mesh.faces[f].vertices);
+            assert_eq!(he.face_neighbours(f).count(), 3);
+        }
+
+        for v in 0..mesh.vertices.len() {
+            assert!(!he.is_boundary_vertex(v));
+
+            let neighbours = he.vertex_neighbours(v);
+            assert_eq!(neighbours.len(), mesh.vertices[v].faces.len());
+            assert_eq!(neighbours.into_iter().collect::<BTreeSet<_>>(), brute_neighbours(&mesh, v));
+
+            let faces : BTreeSet<usize> = he.vertex_faces(v).collect();
+            assert_eq!(faces, mesh.vertices[v].faces.iter().copied().collect());
+            assert!(he.outgoing(v).all(|h| he.half_edges[h].origin == v));
+        }
+
+        assert_same(&he.to_mesh(), &mesh);
+    }
+}
+
+#[test]
+fn test_halfedge_boundary() {
+    // [Scenario] a missing face opens a triangular hole
+    let mut mesh = common::mesh("ascii-cube.stl");
+    let removed = mesh.faces.remove(3);
+    for v in &mut mesh.vertices {
+        v.faces = v.faces.iter().filter(|&&f| f != 3).map(|&f| if f > 3 { f - 1 } else { f }).collect();
+    }
+
+    let he = HalfEdgeMesh::from_mesh(&mesh);
+    let boundary : Vec<usize> = (0..he.half_edges.len()).filter(|&h| he.is_boundary_edge(h)).collect();
+    assert_eq!(boundary.len(), 3);
+
+    for v in 0..mesh.vertices.len() {
+        assert_eq!(he.is_boundary_vertex(v), removed.vertices.contains(&v));
+
+        // The walk around a boundary vertex starts at the hole and still reaches every neighbour.
+        let neighbours = he.vertex_neighbours(v);
+        assert_eq!(neighbours.len(), brute_neighbours(&mesh, v).len());
+        assert_eq!(neighbours.into_iter().collect::<BTreeSet<_>>(), brute_neighbours(&mesh, v));
+        assert_eq!(he.vertex_faces(v).count(), mesh.vertices[v].faces.len());
+    }
+
+    assert_same(&he.to_mesh(), &mesh);
+
+    // [Scenario] a third face on an edge is left without twins
+    let mut mesh = common::mesh("ascii-cube.stl");
+    let [a, b, c] = mesh.faces[0].vertices;
+    mesh.faces.push(vox_graphics::model::FaceInfo { vertices: [b, a, c], color: None, part: 0, normals: None, uvs: None });
+
+    let he = HalfEdgeMesh::from_mesh(&mesh);
+    let [h0, ..] = he.face_half_edges(0);
+    assert!(he.is_boundary_edge(h0));
+    assert!(he.face_neighbours(12).count() < 3);
+}
+
+#[test]
+fn test_halfedge_attributes() {
+    let mut mesh = Mesh::from_ply(&vox_ply::ply::parse_from_file(Path::new("../resources/models/cube-colors.ply")).unwrap()).unwrap();
+    mesh.parts = vec!["a".into(), "b".into()];
+    mesh.faces[1].part = 1;
+    mesh.faces[2].color = Some([1, 2, 3]);
+    mesh.uvs = vec![vox_fwd::Pt2::new(0.0, 0.0), vox_fwd::Pt2::new(1.0, 0.0), vox_fwd::Pt2::new(0.0, 1.0)];
+    mesh.faces[4].uvs = Some([0, 1, 2]);
+
+    let he = HalfEdgeMesh::from_mesh(&mesh);
+    let [h0, h1, _] = he.face_half_edges(4);
+    assert_eq!((he.half_edges[h0].uv, he.half_edges[h1].uv), (Some(0), Some(1)));
+
+    assert_same(&he.to_mesh(), &mesh);
+}
@@ -0,0 +1,100 @@
+use std::sync::Arc;
+
+use nalgebra as na;
+
+use vox_fwd::Pt3;
+use vox_graphics::camera::CameraInfo;
+use vox_graphics::grr;
+use vox_graphics::model::{Lod, Mesh, Model};
+use vox_graphics::surface::Surface;
+
+mod common;
+
+fn mesh(name: &str) -> Arc<Mesh> {
+    Arc::new(common::mesh(name))
+}
+
+/// [returns] a model of the sphere, centred on the origin, with the cube as its only level of detail.
+fn sphere_with_lod(max_pixels: f32) -> Model {
+    let sphere = mesh("ascii-sphere.stl");
+    let center = common::center(&sphere);
+
+    Model {
+        mesh: sphere,
+        transform: na::convert(na::Isometry3::translation(-center.x, -center.y, -center.z)),
+        texture: common::matcap(),
+        base_color: None,
+        lods: vec![Lod::new(mesh("ascii-cube.stl"), max_pixels)]
+    }
+}
+
+/// [returns] the pixels covered by `model`, seen from `distance` away.
+fn render(model: &Model, distance: f32) -> Vec<[u8; 3]> {
+    let camera = CameraInfo::new(Pt3::new(0.0, 0.0, distance), Pt3::origin());
+    let proj = na::Perspective3::new(1.0, 1.0, 1.0, 1000.0);
+
+    let mut surface = Surface::new(64, 64, false);
+    grr::render_model(model, &camera, &proj, &mut surface);
+    surface.to_img().pixels().map(|p| p.0).collect()
+}
+
+#[test]
+fn test_lod_selection() {
+    let model = Model {
+        lods: vec![
+            Lod::new(mesh("ascii-cube.stl"), 100.0),
+            Lod::new(mesh("binary-cube.stl"), 10.0)
+        ],
+        ..sphere_with_lod(0.0)
+    };
+
+    assert!(Arc::ptr_eq(model.mesh_for_size(f32::INFINITY), &model.mesh));
+    assert!(Arc::ptr_eq(model.mesh_for_size(101.0), &model.mesh));
+    assert!(Arc::ptr_eq(model.mesh_for_size(100.0), model.lods[0].mesh()));
+    assert!(Arc::ptr_eq(model.mesh_for_size(50.0), model.lods[0].mesh()));
+    assert!(Arc::ptr_eq(model.mesh_for_size(5.0), model.lods[1].mesh()));
+}
+
+#[test]
+fn test_lod_projected_size() {
+    let model = Model { lods: vec![], ..sphere_with_lod(0.0) };
+    let sphere = model.bounding_sphere();
+    let proj = na::Perspective3::new(1.0, 1.0, 1.0, 1000.0);
+    let surface = Surface::new(64, 64, false);
+
+    let size_at = |distance: f32| {
+        let camera = CameraInfo::new(Pt3::new(0.0, 0.0, distance), Pt3::origin());
+        grr::projected_size(&sphere, &(camera.view_matrix * model.transform), &proj, &surface)
+    };
+
+    // Twice as far away, half as big.
+    let (near, far) = (size_at(100.0), size_at(200.0));
+    assert!((near / far - 2.0).abs() < 0.05, "{} vs {}", near, far);
+
+    // Scaling the model up scales its size on screen too.
+    let mut scaled = model.clone();
+    scaled.transform.set_scaling(2.0);
+    let camera = CameraInfo::new(Pt3::new(0.0, 0.0, 200.0), Pt3::origin());
+    let size = grr::projected_size(&sphere, &(camera.view_matrix * scaled.transform), &proj, &surface);
+    assert!((size / far - 2.0).abs() < 0.05);
+
+    // From inside the sphere it covers everything.
+    assert_eq!(size_at(0.5 * sphere.radius), f32::INFINITY);
+}
+
+#[test]
+fn test_lod_render() {
+    let far = 20.0 * sphere_with_lod(0.0).bounding_sphere().radius;
+
+    // [Scenario] the cube is only allowed at a handful of pixels, so the sphere is drawn
+    let full = render(&sphere_with_lod(1.0), far);
+    let sphere_only = render(&Model { lods: vec![], ..sphere_with_lod(0.0) }, far);
+    assert_eq!(full, sphere_only);
+
+    // [Scenario] the model is small enough for the cube to stand in
+    let lod = render(&sphere_with_lod(1000.0), far);
+    let cube = sphere_with_lod(0.0);
+    let cube_only = render(&Model { mesh: cube.lods[0].mesh().clone(), lods: vec![], ..cube }, far);
+    assert_eq!(lod, cube_only);
+    assert_ne!(lod, full);
+}
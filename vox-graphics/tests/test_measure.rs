@@ -0,0 +1,109 @@
+use std::f32::consts::PI;
+use std::sync::Arc;
+
+use nalgebra as na;
+
+use vox_fwd::{Pt3, Vec3};
+use vox_graphics::model::Model;
+
+mod common;
+
+fn assert_close(a: f32, b: f32, tolerance: f32) {
+    assert!((a - b).abs() <= tolerance, "{} vs {}", a, b);
+}
+
+#[test]
+fn test_measure_cube() {
+    // The unit cube, from the origin to (1, 1, 1).
+    let cube = common::mesh("ascii-cube.stl");
+
+    assert_close(cube.volume(), 1.0, 1e-6);
+    assert_close(cube.surface_area(), 6.0, 1e-6);
+    assert!((cube.centroid().unwrap() - Pt3::new(0.5, 0.5, 0.5)).norm() < 1e-6);
+
+    // For a solid cube of side a and mass m, each moment is m a^2 / 6, with no products of inertia.
+    let inertia = cube.inertia_tensor().unwrap();
+    assert!((inertia - na::Matrix3::identity() / 6.0).norm() < 1e-6, "{}", inertia);
+
+    let aabb = cube.aabb().unwrap();
+    assert_eq!((aabb.min, aabb.max), (Pt3::new(0.0, 0.0, 0.0), Pt3::new(1.0, 1.0, 1.0)));
+
+    let obb = cube.obb().unwrap();
+    assert_close(obb.volume(), 1.0, 1e-5);
+    assert!((obb.center - Pt3::new(0.5, 0.5, 0.5)).norm() < 1e-6);
+
+    // [Scenario] the cube turned inside out
+    let mut inside_out = cube.clone();
+    inside_out.faces.iter_mut().for_each(|f| f.vertices.swap(1, 2));
+    assert_close(inside_out.volume(), -1.0, 1e-6);
+    assert_close(inside_out.surface_area(), 6.0, 1e-6);
+}
+
+#[test]
+fn test_measure_sphere() {
+    // A sphere of radius 0.5 around the origin, made of flat faces (so it's slightly smaller).
+    let sphere = common::mesh("ascii-sphere.stl");
+    let r : f32 = 0.5;
+
+    let volume = 4.0 / 3.0 * PI * r.powi(3);
+    assert!(sphere.volume() < volume);
+    assert_close(sphere.volume(), volume, 0.02 * volume);
+    assert_close(sphere.surface_area(), 4.0 * PI * r * r, 0.02 * 4.0 * PI * r * r);
+    assert!(sphere.centroid().unwrap().coords.norm() < 1e-5);
+
+    // A solid sphere's moment about any axis is 2/5 m r^2.
+    let inertia = sphere.inertia_tensor().unwrap();
+    let moment = 2.0 / 5.0 * sphere.volume() * r * r;
+    for i in 0..3 {
+        assert_close(inertia[(i, i)], moment, 0.02 * moment);
+    }
+    assert!((inertia - na::Matrix3::from_diagonal(&inertia.diagonal())).norm() < 1e-3 * moment);
+
+    let aabb = sphere.aabb().unwrap();
+    assert!((aabb.size() - Vec3::repeat(2.0 * r)).norm() < 1e-3);
+
+    // Every vertex is inside the oriented box.
+    let obb = sphere.obb().unwrap();
+    assert!(obb.volume() <= aabb.volume() + 1e-6);
+    for v in &sphere.vertices {
+        let local = obb.axes.inverse_transform_vector(&(v.vtx.0 - obb.center));
+        assert!(local.iter().zip(obb.half_extents.iter()).all(|(c, h)| c.abs() <= h + 1e-5));
+    }
+}
+
+#[test]
+fn test_measure_model() {
+    let rotation = na::UnitQuaternion::from_axis_angle(&Vec3::z_axis(), PI / 4.0);
+    let model = Model {
+        mesh: Arc::new(common::mesh("ascii-cube.stl")),
+        transform: na::Similarity3::from_parts(na::Translation3::new(10.0, 0.0, 0.0), rotation, 2.0),
+        texture: common::matcap(),
+        base_color: None,
+        lods: vec![]
+    };
+
+    // Twice the size: 8 times the volume, 4 times the area.
+    assert_close(model.volume(), 8.0, 1e-5);
+    assert_close(model.surface_area(), 24.0, 1e-5);
+
+    let centroid = model.centroid().unwrap();
+    assert!((centroid - model.transform * Pt3::new(0.5, 0.5, 0.5)).norm() < 1e-5);
+
+    // A cube's inertia is the same about every axis, so rotating it changes nothing; scaling by s
+    // multiplies it by s^5.
+    let inertia = model.inertia_tensor().unwrap();
+    assert!((inertia - na::Matrix3::identity() * 32.0 / 6.0).norm() < 1e-4, "{}", inertia);
+
+    // The world-space box of the rotated cube is wider than the cube, but its oriented box isn't.
+    let aabb = model.aabb().unwrap();
+    assert_close(aabb.size().x, 2.0 * 2f32.sqrt(), 1e-5);
+    assert_close(aabb.size().z, 2.0, 1e-5);
+
+    let obb = model.obb().unwrap();
+    assert_close(obb.volume(), 8.0, 1e-4);
+    assert!((obb.center - centroid).norm() < 1e-5);
+    for v in &model.mesh.vertices {
+        let local = obb.axes.inverse_transform_vector(&(model.transform * v.vtx.0 - obb.center));
+        assert!(local.iter().zip(obb.half_extents.iter()).all(|(c, h)| c.abs() <= h + 1e-4));
+    }
+}
@@ -0,0 +1,234 @@
+use std::path::Path;
+
+use vox_graphics::model::Mesh;
+use vox_stl::stl;
+
+#[test]
+fn test_mesh_from_facet_reader() {
+    let path = Path::new("../resources/models/ascii-sphere.stl");
+
+    let streamed = Mesh::try_from_facets(stl::FacetReader::open(path).unwrap()).unwrap();
+    let collected = Mesh::from_facets(stl::parse_from_file(path).unwrap());
+
+    assert_eq!(streamed.faces.len(), 960);
+    assert_eq!(streamed.faces.len(), collected.faces.len());
+    assert_eq!(streamed.vertices.len(), collected.vertices.len());
+
+    // [Scenario] the stream fails part-way through
+    let mut bytes = std::fs::read(path).unwrap();
+    let half = bytes[..bytes.len() / 2].iter().rposition(|&b| b == b'\n').unwrap();
+    bytes.truncate(half + 1);
+
+    let result = Mesh::try_from_facets(stl::FacetReader::new(std::io::Cursor::new(bytes)).unwrap());
+    assert!(matches!(result, Err(stl::StlError::UnexpectedEof { .. })));
+}
+
+#[test]
+fn test_mesh_face_colors() {
+    use vox_stl::color::ColorConvention;
+
+    let path = Path::new("../resources/models/binary-cube.stl");
+
+    // [Scenario] an uncoloured file
+    let mesh = Mesh::try_from_stl(stl::FacetReader::open(path).unwrap()).unwrap();
+    assert!(mesh.faces.iter().all(|f| f.color.is_none()));
+
+    // [Scenario] a VisCAM coloured file
+    let mut facets = stl::parse_from_file(path).unwrap();
+    facets[3].attribute = ColorConvention::VisCam.encode([255, 0, 0]);
+
+    let mut bytes = vec![];
+    stl::write_binary(&mut bytes, &facets, None).unwrap();
+
+    let mesh = Mesh::try_from_stl(stl::FacetReader::new(std::io::Cursor::new(bytes)).unwrap()).unwrap();
+    assert_eq!(mesh.faces[3].color, Some([255, 0, 0]));
+    assert_eq!(mesh.faces.iter().filter(|f| f.color.is_some()).count(), 1);
+}
+
+#[test]
+fn test_mesh_parts() {
+    use vox_stl::fwd::Solid;
+
+    let cube = stl::parse_from_file(Path::new("../resources/models/ascii-cube.stl")).unwrap();
+    let sphere = stl::parse_from_file(Path::new("../resources/models/ascii-sphere.stl")).unwrap();
+
+    let solids = vec![
+        Solid { name: "cube".to_string(), facets: cube },
+        Solid { name: "sphere".to_string(), facets: sphere },
+    ];
+
+    let mut bytes = vec![];
+    stl::write_ascii_solids(&mut bytes, &solids).unwrap();
+
+    let mesh = Mesh::try_from_stl(stl::FacetReader::new(std::io::Cursor::new(bytes)).unwrap()).unwrap();
+    assert_eq!(mesh.parts, vec!["cube".to_string(), "sphere".to_string()]);
+    assert_eq!(mesh.faces.iter().filter(|f| f.part == 0).count(), 12);
+    assert_eq!(mesh.faces.iter().filter(|f| f.part == 1).count(), 960);
+
+    let parts = mesh.split_parts();
+    assert_eq!(parts.len(), 2);
+    assert_eq!(parts[0].parts, vec!["cube".to_string()]);
+    assert_eq!(parts[0].faces.len(), 12);
+    assert_eq!(parts[0].vertices.len(), 8);
+    assert_eq!(parts[1].faces.len(), 960);
+    assert!(parts[1].faces.iter().all(|f| f.part == 0));
+
+    assert_eq!(Mesh::from_solids(solids).parts.len(), 2);
+
+    // [Scenario] a plain facet list is a single unnamed part
+    let mesh = Mesh::from_facets(stl::parse_from_file(Path::new("../resources/models/ascii-cube.stl")).unwrap());
+    assert_eq!(mesh.parts, vec![String::new()]);
+    assert_eq!(mesh.split_parts()[0].faces.len(), 12);
+}
+
+#[test]
+fn test_mesh_from_obj() {
+    let data = vox_obj::obj::parse_from_file(Path::new("../resources/models/cube-quads.obj")).unwrap();
+    let mesh = Mesh::from_obj(&data);
+
+    assert_eq!(mesh.faces.len(), 12);
+    assert_eq!(mesh.vertices.len(), 8);
+    assert_eq!(mesh.normals.len(), 6);
+    assert_eq!(mesh.uvs.len(), 4);
+    assert_eq!(mesh.parts, vec!["cube/sides".to_string(), "cube/caps".to_string()]);
+
+    // Every corner of the cube is shared by the faces of 3 sides.
+    assert!(mesh.vertices.iter().all(|v| v.faces.len() >= 3));
+
+    // Normals and UVs are kept per corner.
+    assert!(mesh.faces.iter().all(|f| f.normals.is_some() && f.uvs.is_some()));
+    let bottom = &mesh.faces[8];
+    assert_eq!(bottom.normals, Some([0, 0, 0]));
+    assert_eq!(mesh.normals[0], vox_fwd::Vec3::new(0.0, 0.0, -1.0));
+
+    // The welded STL cube has the same topology.
+    let stl_mesh = Mesh::from_facets(stl::parse_from_file(Path::new("../resources/models/ascii-cube.stl")).unwrap());
+    assert_eq!(stl_mesh.vertices.len(), mesh.vertices.len());
+    assert!(stl_mesh.faces.iter().all(|f| f.normals.is_none() && f.uvs.is_none()));
+}
+
+#[test]
+fn test_mesh_from_ply() {
+    use vox_ply::fwd::Format;
+    use vox_ply::ply::{self, PlyError};
+
+    let data = ply::parse_from_file(Path::new("../resources/models/cube-colors.ply")).unwrap();
+    let mesh = Mesh::from_ply(&data).unwrap();
+
+    assert_eq!(mesh.vertices.len(), 8);
+    assert_eq!(mesh.faces.len(), 12);
+    assert_eq!(mesh.vertices[2].color, Some([0, 0, 255]));
+    assert!(mesh.faces.iter().all(|f| f.color.is_none() && f.normals.is_none()));
+
+    // [Scenario] the mesh survives being written out and read back, in every format
+    for format in [Format::Ascii, Format::BinaryLittleEndian, Format::BinaryBigEndian] {
+        let mut bytes = vec![];
+        ply::write(&mut bytes, &mesh.to_ply(format)).unwrap();
+
+        let reread = Mesh::from_ply(&ply::parse_from_bytes(&bytes).unwrap()).unwrap();
+        assert_eq!(reread.faces.iter().map(|f| f.vertices).collect::<Vec<_>>(), mesh.faces.iter().map(|f| f.vertices).collect::<Vec<_>>());
+        assert_eq!(reread.vertices.iter().map(|v| (v.vtx.0, v.color)).collect::<Vec<_>>(), mesh.vertices.iter().map(|v| (v.vtx.0, v.color)).collect::<Vec<_>>());
+    }
+
+    // [Scenario] a face refers to a vertex that doesn't exist
+    let mut bad = data.clone();
+    bad.elements[1].rows[4][0] = vox_ply::fwd::Value::List(vec![2.0, 3.0, 8.0]);
+    assert!(matches!(Mesh::from_ply(&bad), Err(PlyError::BadIndex { face: 4, index }) if index == 8.0));
+
+    // [Scenario] normals and texture coordinates are per-vertex
+    let src = "ply\nformat ascii 1.0\nelement vertex 3\nproperty float x\nproperty float y\nproperty float z\n\
+               property float nx\nproperty float ny\nproperty float nz\nproperty float u\nproperty float v\n\
+               element face 1\nproperty list uchar uint vertex_index\nend_header\n\
+               0 0 0 0 0 1 0 0\n1 0 0 0 0 1 1 0\n0 1 0 0 0 1 0 1\n3 0 1 2\n";
+    let mesh = Mesh::from_ply(&ply::parse_from_bytes(src.as_bytes()).unwrap()).unwrap();
+    assert_eq!(mesh.normals.len(), 3);
+    assert_eq!(mesh.uvs[2].y, 1.0);
+    assert_eq!(mesh.faces[0].normals, Some([0, 1, 2]));
+
+    let reread = mesh.to_ply(Format::Ascii);
+    assert!(reread.element("vertex").unwrap().property_index("nx").is_some());
+    assert!(reread.element("vertex").unwrap().property_index("s").is_some());
+}
+
+#[test]
+fn test_models_from_gltf() {
+    use std::sync::Arc;
+    use vox_graphics::model::Model;
+    use vox_graphics::texture::MatcapTexture;
+
+    let matcap = Arc::new(MatcapTexture::from_file(Path::new("../resources/matcaps/normal_256.png")));
+    let scene = vox_gltf::gltf::parse_from_file(Path::new("../resources/models/gltf/quad.glb")).unwrap();
+
+    let models = Model::from_gltf(&scene, matcap);
+    assert_eq!(models.len(), 2);
+
+    // The child is rotated a quarter turn about z, scaled by 2 and moved by (1, 2, 3).
+    let child = &models[0];
+    assert_eq!(child.mesh.faces.len(), 2);
+    assert_eq!(child.mesh.parts, vec!["quad".to_string()]);
+    assert!((child.transform.scaling() - 2.0).abs() < 1e-5);
+
+    let corner = child.mesh.get_vertex(1).to_world(&child.transform).0;
+    assert!((corner - vox_fwd::Pt3::new(1.0, 4.0, 3.0)).norm() < 1e-5);
+
+    // Texture coordinates are flipped to have v pointing up, and faces take the base colour.
+    assert_eq!(child.mesh.uvs[0], vox_fwd::Pt2::new(0.0, 0.0));
+    assert_eq!(child.mesh.faces[0].color, Some([255, 186, 136]));
+    assert_eq!(child.base_color.as_ref().unwrap().dimensions(), (2, 2));
+
+    // [Scenario] a non-uniform scale can't be a Similarity3, so it's baked into a copy of the mesh
+    let stretched = &models[1];
+    assert!(!Arc::ptr_eq(&stretched.mesh, &child.mesh));
+    assert_eq!(stretched.transform, nalgebra::Similarity3::identity());
+    assert_eq!(stretched.mesh.get_vertex(2).0, vox_fwd::Pt3::new(1.0, 2.0, 0.0));
+}
+
+#[test]
+fn test_mesh_from_off() {
+    let data = vox_off::off::parse_from_file(Path::new("../resources/models/cube.off")).unwrap();
+    let mesh = Mesh::from_off(&data);
+
+    assert_eq!(mesh.vertices.len(), 8);
+    assert_eq!(mesh.faces.len(), 12);
+    assert_eq!(mesh.vertices[5].color, Some([0, 255, 255]));
+    assert_eq!(mesh.faces.iter().filter(|f| f.color == Some([255, 0, 0])).count(), 2);
+
+    // [Scenario] the triangulated mesh survives being written out and read back
+    let mut bytes = vec![];
+    vox_off::off::write(&mut bytes, &mesh.to_off()).unwrap();
+
+    let reread = Mesh::from_off(&vox_off::off::parse_from_bytes(&bytes).unwrap());
+    assert_eq!(reread.faces.iter().map(|f| (f.vertices, f.color)).collect::<Vec<_>>(), mesh.faces.iter().map(|f| (f.vertices, f.color)).collect::<Vec<_>>());
+    assert_eq!(reread.vertices.iter().map(|v| v.color).collect::<Vec<_>>(), mesh.vertices.iter().map(|v| v.color).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_models_from_3mf() {
+    use std::sync::Arc;
+    use vox_graphics::model::Model;
+    use vox_graphics::texture::MatcapTexture;
+
+    let matcap = Arc::new(MatcapTexture::from_file(Path::new("../resources/matcaps/normal_256.png")));
+    let package = vox_3mf::three_mf::parse_from_file(Path::new("../resources/models/tetra-pair.3mf")).unwrap();
+
+    let models = Model::from_3mf(&package, matcap);
+    assert_eq!(models.len(), 3);
+    assert_eq!(models[0].mesh.parts, vec!["tetra".to_string()]);
+    assert_eq!(models[0].mesh.faces.len(), 4);
+
+    // The pair shares one mesh, and its second copy is moved 20 along x (turned into y by the item).
+    assert!(Arc::ptr_eq(&models[0].mesh, &models[1].mesh));
+    let apex = models[1].mesh.get_vertex(1).to_world(&models[1].transform).0;
+    assert!((apex - vox_fwd::Pt3::new(5.0, 35.0, 0.0)).norm() < 1e-5);
+
+    // [Scenario] the last item is stretched along y, so it's baked into its own copy
+    assert!(!Arc::ptr_eq(&models[0].mesh, &models[2].mesh));
+    assert_eq!(models[2].mesh.get_vertex(2).0, vox_fwd::Pt3::new(0.0, 70.0, 0.0));
+
+    // [Scenario] the models can be handed back as a package
+    let package = Model::to_3mf(&models);
+    assert_eq!(package.objects.len(), 2);
+    assert_eq!(package.build.len(), 3);
+    assert_eq!(package.build[1].object_id, package.build[0].object_id);
+    assert_eq!(package.build[1].transform[3], [5.0, 25.0, 0.0, 1.0]);
+}
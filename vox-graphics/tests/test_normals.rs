@@ -0,0 +1,102 @@
+use std::collections::HashSet;
+use std::f32::consts::PI;
+use std::sync::Arc;
+
+use nalgebra as na;
+
+use vox_fwd::{Pt3, Vec3};
+use vox_graphics::camera::CameraInfo;
+use vox_graphics::grr;
+use vox_graphics::model::{Mesh, Model};
+use vox_graphics::normals::NormalWeighting;
+use vox_graphics::surface::Surface;
+
+mod common;
+
+#[test]
+fn test_normals_crease_angle() {
+    // [Scenario] the cube's edges are all sharper than the crease angle
+    let mut cube = common::mesh("ascii-cube.stl");
+    cube.compute_normals(30f32.to_radians(), NormalWeighting::Angle);
+
+    // Each vertex has one normal per side of the cube that it is on.
+    assert_eq!(cube.normals.len(), 24);
+    for face in &cube.faces {
+        let [p0, p1, p2] = face.vertices.map(|v| cube.vertices[v].vtx.0);
+        let flat = (p1 - p0).cross(&(p2 - p0)).normalize();
+
+        for n in face.normals.unwrap() {
+            assert!((cube.normals[n] - flat).norm() < 1e-6);
+        }
+    }
+
+    // [Scenario] a crease angle of pi smooths over the edges
+    for weighting in [NormalWeighting::Angle, NormalWeighting::Area] {
+        cube.compute_normals(PI, weighting);
+        assert_eq!(cube.normals.len(), 8);
+        assert!(cube.normals.iter().all(|n| (n.norm() - 1.0).abs() < 1e-6));
+    }
+
+    // With angle weighting the corner normals point along the diagonals, whichever way the sides
+    // of the cube were split into triangles.
+    cube.compute_normals(PI, NormalWeighting::Angle);
+    for n in &cube.normals {
+        assert!(n.iter().all(|c| (c.abs() - 1.0 / 3f32.sqrt()).abs() < 1e-5), "{:?}", n);
+    }
+}
+
+#[test]
+fn test_normals_sphere() {
+    let mut sphere = common::mesh("ascii-sphere.stl");
+    sphere.compute_normals(60f32.to_radians(), NormalWeighting::Area);
+
+    // The sphere is smooth everywhere, so every vertex has a single normal, close to the radius.
+    assert_eq!(sphere.normals.len(), sphere.vertices.len());
+
+    let center = sphere.vertices.iter().map(|v| v.vtx.0.coords).sum::<Vec3>() / sphere.vertices.len() as f32;
+    for face in &sphere.faces {
+        for (v, n) in face.vertices.iter().zip(face.normals.unwrap()) {
+            let radial = (sphere.vertices[*v].vtx.0.coords - center).normalize();
+            assert!(radial.dot(&sphere.normals[n]) > 0.99);
+        }
+    }
+}
+
+#[test]
+fn test_normals_smooth_shading() {
+    let render = |mesh: Mesh| {
+        let center = mesh.vertices.iter().map(|v| v.vtx.0.coords).sum::<Vec3>() / mesh.vertices.len() as f32;
+        let radius = mesh.vertices.iter().map(|v| (v.vtx.0.coords - center).norm()).fold(0.0, f32::max);
+
+        let model = Model {
+            mesh: Arc::new(mesh),
+            transform: na::convert(na::Isometry3::translation(-center.x, -center.y, -center.z)),
+            texture: common::matcap(),
+            base_color: None,
+            lods: vec![]
+        };
+
+        let camera = CameraInfo::new(Pt3::new(0.0, 0.0, 3.0 * radius), Pt3::origin());
+        let proj = na::Perspective3::new(1.0, 1.0, radius, 10.0 * radius);
+
+        let mut surface = Surface::new(128, 128, false);
+        grr::render_model(&model, &camera, &proj, &mut surface);
+
+        let img = surface.to_img();
+        let colors : HashSet<[u8; 3]> = img.pixels().map(|p| p.0).filter(|&c| c != [0, 0, 0]).collect();
+        let covered = img.pixels().filter(|p| p.0 != [0, 0, 0]).count();
+        (colors.len(), covered)
+    };
+
+    let flat = common::mesh("ascii-sphere.stl");
+    let mut smooth = flat.clone();
+    smooth.compute_normals(60f32.to_radians(), NormalWeighting::Angle);
+
+    let (flat_colors, flat_covered) = render(flat);
+    let (smooth_colors, smooth_covered) = render(smooth);
+
+    // The same pixels are covered, but they're shaded per pixel rather than per face.
+    assert_eq!(flat_covered, smooth_covered);
+    assert!(flat_colors > 0);
+    assert!(smooth_colors > 2 * flat_colors, "{} vs {}", smooth_colors, flat_colors);
+}
@@ -0,0 +1,137 @@
+use nalgebra as na;
+
+use vox_fwd::{Pt3, Vec3};
+use vox_graphics::camera::CameraInfo;
+use vox_graphics::clipping::BoundingSphere;
+use vox_graphics::grr;
+use vox_graphics::scene::{Camera, Light, Node, Scene};
+use vox_graphics::surface::Surface;
+
+mod common;
+
+fn translation(x: f32, y: f32, z: f32) -> na::Similarity3<f32> {
+    na::convert(na::Isometry3::translation(x, y, z))
+}
+
+fn projection() -> na::Perspective3<f32> {
+    na::Perspective3::new(1.0, 1.0, 0.1, 100.0)
+}
+
+/// [returns] a scene with a camera 2 units up +z, looking back at the origin.
+fn scene_with_camera() -> (Scene, usize) {
+    let mut scene = Scene::new();
+    let camera = scene.add(None, Node {
+        transform: translation(0.0, 0.0, 2.0),
+        camera: Some(Camera { projection: projection() }),
+        ..Node::new("camera")
+    });
+    (scene, camera)
+}
+
+fn render(scene: &Scene, camera: usize) -> Vec<[u8; 3]> {
+    let mut surface = Surface::new(64, 64, false);
+    grr::render_scene(scene, camera, &mut surface);
+    surface.to_img().pixels().map(|p| p.0).collect()
+}
+
+#[test]
+fn test_scene_hierarchy() {
+    let mut scene = Scene::new();
+    let mut parent = Node::new("parent");
+    parent.transform = translation(1.0, 0.0, 0.0);
+    parent.transform.set_scaling(2.0);
+
+    let parent = scene.add(None, parent);
+    let child = scene.add(Some(parent), Node { transform: translation(0.0, 1.0, 0.0), ..Node::new("child") });
+    let other = scene.add(None, Node::new("other"));
+    let grandchild = scene.add(Some(child), Node { model: Some(common::sphere()), ..Node::new("grandchild") });
+
+    assert_eq!(scene.find("child"), Some(child));
+    assert_eq!(scene.find("nobody"), None);
+    assert_eq!(scene.parent(grandchild), Some(child));
+    assert_eq!(scene.parent(other), None);
+    assert_eq!(scene.children(parent).collect::<Vec<_>>(), vec![child]);
+
+    // [Scenario] the child's offset is scaled by its parent
+    let world = scene.world_transform(grandchild);
+    assert!((world * Pt3::origin() - Pt3::new(1.0, 2.0, 0.0)).norm() < 1e-6);
+    assert_eq!(world.scaling(), 2.0);
+
+    let all = scene.world_transforms();
+    assert_eq!(all.len(), scene.nodes().len());
+    for (i, transform) in all.iter().enumerate() {
+        assert!((transform.to_homogeneous() - scene.world_transform(i).to_homogeneous()).norm() < 1e-6);
+    }
+
+    // [Scenario] models are placed by their nodes, after their own transform
+    let models = scene.world_models();
+    assert_eq!(models.len(), 1);
+    let center = models[0].transform * BoundingSphere::from(&models[0].mesh.vertices.iter().map(|v| v.vtx.0).collect::<Vec<_>>()).center;
+    assert!((center - Pt3::new(1.0, 2.0, 0.0)).norm() < 1e-4);
+
+    // [Scenario] moving a parent moves its descendants
+    scene.node_mut(parent).transform.append_translation_mut(&na::Translation3::new(0.0, 0.0, 3.0));
+    assert!((scene.world_transform(grandchild) * Pt3::origin() - Pt3::new(1.0, 2.0, 3.0)).norm() < 1e-6);
+}
+
+#[test]
+fn test_scene_render() {
+    let (mut scene, camera) = scene_with_camera();
+
+    // [Scenario] the camera node sees what the same camera looking at the origin would
+    let (view, _) = scene.camera(camera).unwrap();
+    let expected = CameraInfo::new(Pt3::new(0.0, 0.0, 2.0), Pt3::origin());
+    assert!((view.view_matrix.to_homogeneous() - expected.view_matrix.to_homogeneous()).norm() < 1e-6);
+    let empty = scene.add(None, Node::new("empty"));
+    assert!(scene.camera(empty).is_none());
+
+    // [Scenario] a single model renders as it does on its own
+    let group = scene.add(None, Node { transform: translation(0.0, 0.0, 1.0), ..Node::new("group") });
+    scene.add(Some(group), Node { transform: translation(0.0, 0.0, -1.0), model: Some(common::sphere()), ..Node::new("near") });
+
+    let mut alone = Surface::new(64, 64, false);
+    grr::render_model(&common::sphere(), &view, &projection(), &mut alone);
+    let alone : Vec<[u8; 3]> = alone.to_img().pixels().map(|p| p.0).collect();
+    assert_eq!(render(&scene, camera), alone);
+
+    // [Scenario] models share the z-buffer, so a model further away doesn't draw over a nearer one
+    scene.add(Some(group), Node { transform: translation(0.6, 0.0, -3.0), model: Some(common::sphere()), ..Node::new("far") });
+    let both = render(&scene, camera);
+    assert_ne!(both, alone);
+    for (b, a) in both.iter().zip(&alone) {
+        if *a != [0; 3] {
+            assert_eq!(b, a);
+        }
+    }
+}
+
+#[test]
+fn test_scene_lights() {
+    let (mut scene, camera) = scene_with_camera();
+    scene.ambient = 0.1;
+    scene.add(None, Node { model: Some(common::sphere()), ..Node::new("sphere") });
+
+    // A white light shining down -y (so from +y), by turning -z to -y.
+    let rotation = na::UnitQuaternion::from_axis_angle(&Vec3::x_axis(), -std::f32::consts::FRAC_PI_2);
+    let light = scene.add(None, Node {
+        transform: na::Similarity3::from_parts(na::Translation3::identity(), rotation, 1.0),
+        light: Some(Light { color: [255; 3], intensity: 1.0 }),
+        ..Node::new("light")
+    });
+
+    let lights = scene.world_lights();
+    assert_eq!(lights.len(), 1);
+    assert!((lights[0].0 - Vec3::y()).norm() < 1e-6);
+
+    // [Scenario] the side facing the light is lit, and the side away from it only has ambient light
+    // (the image's rows run down from view-space -y to +y)
+    let pixels = render(&scene, camera);
+    let (away, towards) = (pixels[20 * 64 + 32], pixels[44 * 64 + 32]);
+    assert_eq!(away, [25; 3]);
+    assert!(towards[0] > 150, "{:?}", towards);
+
+    // [Scenario] dimming the light
+    scene.node_mut(light).light.as_mut().unwrap().intensity = 0.5;
+    let dimmed = render(&scene, camera)[44 * 64 + 32];
+    assert!(dimmed[0] < towards[0] && dimmed[0] > 25);
+}
@@ -0,0 +1,125 @@
+use nalgebra as na;
+
+use vox_fwd::{Pt3, Vec3};
+use vox_graphics::camera::CameraInfo;
+use vox_graphics::grr;
+use vox_graphics::model::Model;
+use vox_graphics::raster::Fragment;
+use vox_graphics::shader::{corner_normal, FragmentShader, LambertShader, MatcapShader, Uniforms, VertexShader};
+use vox_graphics::surface::Surface;
+
+mod common;
+
+/// [returns] the surface with `model` drawn on it by the shaders.
+fn render<VS: VertexShader>(model: &Model, vertex_shader: &VS, fragment_shader: &impl FragmentShader<VS::Varying>) -> Surface {
+    let camera = CameraInfo::new(Pt3::new(0.0, 0.0, 2.0), Pt3::origin());
+    let proj = na::Perspective3::new(1.0, 1.0, 0.1, 100.0);
+
+    let mut surface = Surface::new(64, 64, false);
+    grr::render_model_with(model, &camera, &proj, &mut surface, vertex_shader, fragment_shader);
+    surface
+}
+
+fn pixel(surface: &Surface, x: u32, y: u32) -> [u8; 3] {
+    surface.to_img().get_pixel(x, y).0
+}
+
+/// Shows the distance from the camera, from white (near) to black (far).
+struct DepthShader {
+    near: f32,
+    far: f32
+}
+
+impl VertexShader for DepthShader {
+    type Varying = f32;
+
+    fn varying(&self, _: &Uniforms, _: usize, corner: usize, view: &[Pt3; 3]) -> f32 {
+        -view[corner].z
+    }
+}
+
+impl FragmentShader<f32> for DepthShader {
+    fn color(&self, _: &Uniforms, _: usize, fragment: &Fragment<f32>) -> Option<[u8; 3]> {
+        let brightness = (self.far - fragment.varying) / (self.far - self.near);
+        Some([(brightness.clamp(0.0, 1.0) * 255.0) as u8; 3])
+    }
+}
+
+/// Shows the view-space normal as a colour, and leaves out faces that point down.
+struct NormalShader;
+
+impl VertexShader for NormalShader {
+    type Varying = Vec3;
+
+    fn varying(&self, uniforms: &Uniforms, face: usize, corner: usize, view: &[Pt3; 3]) -> Vec3 {
+        corner_normal(uniforms, face, corner, view)
+    }
+}
+
+impl FragmentShader<Vec3> for NormalShader {
+    fn color(&self, _: &Uniforms, _: usize, fragment: &Fragment<Vec3>) -> Option<[u8; 3]> {
+        let normal = fragment.varying.normalize();
+        if normal.y < 0.0 {
+            return None;
+        }
+
+        Some([0, 1, 2].map(|i| ((normal[i] + 1.0) * 127.5) as u8))
+    }
+}
+
+#[test]
+fn test_shader_matcap_default() {
+    let model = common::sphere();
+
+    // [Scenario] render_model is render_model_with the matcap shader
+    let camera = CameraInfo::new(Pt3::new(0.0, 0.0, 2.0), Pt3::origin());
+    let proj = na::Perspective3::new(1.0, 1.0, 0.1, 100.0);
+    let mut surface = Surface::new(64, 64, false);
+    grr::render_model(&model, &camera, &proj, &mut surface);
+
+    assert_eq!(surface.to_img(), render(&model, &MatcapShader, &MatcapShader).to_img());
+}
+
+#[test]
+fn test_shader_custom_varyings() {
+    let model = common::sphere();
+
+    // [Scenario] the middle of the sphere is nearer than its edges, and the background is untouched
+    let depth = render(&model, &DepthShader { near: 1.0, far: 2.0 }, &DepthShader { near: 1.0, far: 2.0 });
+    let middle = pixel(&depth, 32, 32)[0];
+    let edge = pixel(&depth, 32, 18)[0];
+    assert!(middle > edge && edge > 0, "{} vs {}", middle, edge);
+    assert_eq!(pixel(&depth, 0, 0), [0; 3]);
+
+    // [Scenario] the middle of the sphere faces the camera (give or take its facets)
+    let normals = render(&model, &NormalShader, &NormalShader);
+    let [r, g, b] = pixel(&normals, 32, 32);
+    assert!(r.abs_diff(127) < 16 && g.abs_diff(127) < 16 && b > 240, "{:?}", [r, g, b]);
+}
+
+#[test]
+fn test_shader_discard() {
+    let model = common::sphere();
+    let surface = render(&model, &NormalShader, &NormalShader);
+
+    // [Scenario] discarded fragments leave the colour and the depth as they were (the image's rows
+    // run down from view-space -y to +y)
+    let (down, up) = (pixel(&surface, 32, 20), pixel(&surface, 32, 44));
+    assert_eq!(down, [0; 3]);
+    assert_ne!(up, [0; 3]);
+    assert_eq!(surface.get_z(32, 20), 0.0);
+    assert!(surface.get_z(32, 44) > 0.0);
+}
+
+#[test]
+fn test_shader_lambert() {
+    let model = common::sphere();
+    let lambert = LambertShader { light: Vec3::y(), ambient: 0.1, color: [200, 100, 50] };
+    let surface = render(&model, &lambert, &lambert);
+
+    // [Scenario] the side facing the light is lit, and the side away from it only has ambient light
+    let (away, towards) = (pixel(&surface, 32, 20), pixel(&surface, 32, 44));
+    assert!(towards[0] > 100, "{:?}", towards);
+    assert_eq!(away, [20, 10, 5]);
+}
+
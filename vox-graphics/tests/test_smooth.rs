@@ -0,0 +1,107 @@
+use vox_fwd::Vec3;
+use vox_graphics::model::Mesh;
+use vox_graphics::smooth::{Pinning, Smoothing};
+use vox_stl::fwd::{Facet, Pt3};
+
+mod common;
+
+/// [returns] the sphere fixture (of radius 0.5, around the origin), with each vertex pushed in or
+///           out by up to `noise` (deterministically).
+fn noisy_sphere(noise: f32) -> Mesh {
+    let mut sphere = common::mesh("ascii-sphere.stl");
+    let mut seed = 7u32;
+
+    for v in &mut sphere.vertices {
+        seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+        let offset = noise * ((seed >> 16) as f32 / 32768.0 - 1.0);
+        v.vtx.0 += v.vtx.0.coords.normalize() * offset;
+    }
+
+    sphere
+}
+
+/// [returns] how bumpy the surface is: the average difference between the distance of each vertex
+///           from the origin and that of its neighbours.
+fn roughness(mesh: &Mesh) -> f32 {
+    let radius = |v: usize| mesh.vertices[v].vtx.0.coords.norm();
+
+    let total : f32 = (0..mesh.vertices.len()).map(|v| {
+        let neighbours : Vec<usize> = mesh.vertices[v].faces.iter()
+            .flat_map(|&f| mesh.faces[f].vertices)
+            .filter(|&u| u != v)
+            .collect();
+        let average = neighbours.iter().map(|&u| radius(u)).sum::<f32>() / neighbours.len() as f32;
+        (radius(v) - average).abs()
+    }).sum();
+
+    total / mesh.vertices.len() as f32
+}
+
+/// [returns] a flat, open grid of `n` by `n` squares from (0, 0) to (1, 1), with bumps in z.
+fn bumpy_grid(n: usize) -> Mesh {
+    let corner = |i: usize, j: usize| Pt3([i as f32 / n as f32, j as f32 / n as f32, 0.1 * ((i * 7 + j * 3) % 5) as f32]);
+    let facet = |tri| Facet { tri, normal: Pt3([0.0, 0.0, 1.0]), attribute: 0 };
+
+    Mesh::from_facets((0..n).flat_map(|i| (0..n).flat_map(move |j| [
+        facet([corner(i, j), corner(i + 1, j), corner(i + 1, j + 1)]),
+        facet([corner(i, j), corner(i + 1, j + 1), corner(i, j + 1)])
+    ])))
+}
+
+#[test]
+fn test_smooth_noisy_sphere() {
+    let noisy = roughness(&noisy_sphere(0.02));
+    let volume = common::mesh("ascii-sphere.stl").volume();
+
+    for method in [
+        Smoothing::Laplacian { lambda: 0.5 },
+        Smoothing::Cotangent { lambda: 0.5 },
+        Smoothing::Taubin { lambda: 0.5, mu: -0.53 }
+    ] {
+        let mut sphere = noisy_sphere(0.02);
+        sphere.smooth(method, 10, &Pinning::default());
+
+        let smoothed = roughness(&sphere);
+        assert!(smoothed < noisy / 4.0, "{:?}: {} vs {}", method, smoothed, noisy);
+
+        // Only Taubin smoothing keeps the volume.
+        let shrinkage = 1.0 - sphere.volume() / volume;
+        match method {
+            Smoothing::Taubin { .. } => assert!(shrinkage.abs() < 0.02, "{}", shrinkage),
+            _ => assert!(shrinkage > 0.05, "{:?}: {}", method, shrinkage)
+        }
+    }
+}
+
+#[test]
+fn test_smooth_pinning() {
+    // [Scenario] the boundary of an open grid stays put
+    let mut grid = bumpy_grid(8);
+    let before : Vec<Vec3> = grid.vertices.iter().map(|v| v.vtx.0.coords).collect();
+    grid.smooth(Smoothing::Laplacian { lambda: 0.5 }, 20, &Pinning { boundary: true, feature_angle: None });
+
+    for (v, p) in grid.vertices.iter().zip(&before) {
+        let on_boundary = [p.x, p.y].iter().any(|&c| c == 0.0 || c == 1.0);
+        if on_boundary {
+            assert_eq!(v.vtx.0.coords, *p);
+        }
+    }
+
+    let inner_moved = grid.vertices.iter().zip(&before).filter(|(v, p)| v.vtx.0.coords != **p).count();
+    assert_eq!(inner_moved, 7 * 7);
+
+    // [Scenario] without pinning, the corners are pulled in
+    let mut grid = bumpy_grid(8);
+    grid.smooth(Smoothing::Laplacian { lambda: 0.5 }, 20, &Pinning::default());
+    assert!(grid.vertices.iter().all(|v| v.vtx.0 != vox_fwd::Pt3::origin()));
+
+    // [Scenario] the edges of a cube are features
+    let cube = common::mesh("ascii-cube.stl");
+    let mut pinned = cube.clone();
+    pinned.smooth(Smoothing::Cotangent { lambda: 0.5 }, 5, &Pinning { boundary: false, feature_angle: Some(30f32.to_radians()) });
+    assert!(pinned.vertices.iter().zip(&cube.vertices).all(|(a, b)| a.vtx.0 == b.vtx.0));
+
+    let mut unpinned = cube.clone();
+    unpinned.smooth(Smoothing::Laplacian { lambda: 0.5 }, 5, &Pinning::default());
+    assert!(unpinned.volume() < 0.5 * cube.volume());
+}
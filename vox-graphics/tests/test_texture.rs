@@ -0,0 +1,139 @@
+use std::sync::Arc;
+
+use image::{Rgba, RgbaImage};
+use nalgebra as na;
+
+use vox_fwd::{Pt2, Pt3};
+use vox_graphics::camera::CameraInfo;
+use vox_graphics::grr;
+use vox_graphics::model::{FaceInfo, Mesh, Model, VertexInfo, VertexModel};
+use vox_graphics::surface::Surface;
+use vox_graphics::texture::{Filter, Texture2D, Wrap};
+
+mod common;
+
+/// [returns] an image one texel high, with the given greys.
+fn row(greys: &[u8]) -> RgbaImage {
+    RgbaImage::from_fn(greys.len() as u32, 1, |x, _| Rgba([greys[x as usize], 0, 0, 255]))
+}
+
+/// [returns] the red channel of `texture` at `u` (in the middle of the row), from 0 to 255.
+fn red(texture: &Texture2D, u: f32, lod: f32) -> f32 {
+    texture.sample(&Pt2::new(u, 0.5), lod).x * 255.0
+}
+
+#[test]
+fn test_texture_mipmaps() {
+    let texture = Texture2D::new(RgbaImage::from_fn(4, 2, |x, y| Rgba([(x * 40 + y * 100) as u8, 0, 0, 255])));
+
+    // [Scenario] each mipmap is half the size of the last, down to 1x1
+    let sizes : Vec<(u32, u32)> = texture.levels().iter().map(|l| l.dimensions()).collect();
+    assert_eq!(sizes, vec![(4, 2), (2, 1), (1, 1)]);
+
+    // [Scenario] each texel is the average of the four it covers
+    assert_eq!(texture.levels()[1].get_pixel(0, 0).0, [70, 0, 0, 255]);
+    assert_eq!(texture.levels()[1].get_pixel(1, 0).0, [150, 0, 0, 255]);
+    assert_eq!(texture.levels()[2].get_pixel(0, 0).0, [110, 0, 0, 255]);
+
+    // [Scenario] odd sizes round down
+    let odd = Texture2D::new(RgbaImage::new(3, 3));
+    assert_eq!(odd.levels().iter().map(|l| l.dimensions()).collect::<Vec<_>>(), vec![(3, 3), (1, 1)]);
+
+    // [Scenario] the level of detail goes up by one each time a pixel covers twice as many texels across
+    let square = Texture2D::new(RgbaImage::new(4, 4));
+    assert_eq!(square.level_of_detail(1.0 / 16.0), 0.0);
+    assert_eq!(square.level_of_detail(1.0), 2.0);
+}
+
+#[test]
+fn test_texture_wrap_and_filter() {
+    let mut texture = Texture2D::new(row(&[0, 85, 170, 255]));
+    texture.filter = Filter::Nearest;
+
+    // [Scenario] past either edge
+    for (wrap, past_right, past_left) in [(Wrap::Repeat, 0.0, 255.0), (Wrap::Clamp, 255.0, 0.0), (Wrap::Mirror, 255.0, 0.0)] {
+        texture.wrap = wrap;
+        assert_eq!(red(&texture, 1.1, 0.0), past_right, "{:?}", wrap);
+        assert_eq!(red(&texture, -0.1, 0.0), past_left, "{:?}", wrap);
+        assert_eq!(red(&texture, 0.6, 0.0), 170.0);
+    }
+
+    // [Scenario] mirroring repeats every other copy backwards
+    texture.wrap = Wrap::Mirror;
+    assert_eq!(red(&texture, 1.6, 0.0), 85.0);
+    assert_eq!(red(&texture, 2.6, 0.0), 170.0);
+
+    // [Scenario] bilinear filtering blends the texels either side, and nearest ignores the level of detail
+    assert_eq!(red(&texture, 0.25, 5.0), 85.0);
+    texture.filter = Filter::Bilinear;
+    texture.wrap = Wrap::Clamp;
+    assert!((red(&texture, 0.25, 0.0) - 42.5).abs() < 1e-3);
+    assert!((red(&texture, 0.125, 0.0) - 0.0).abs() < 1e-3);
+
+    // [Scenario] trilinear filtering blends between the mipmaps
+    let mut texture = Texture2D::new(row(&[0, 255]));
+    texture.wrap = Wrap::Clamp;
+    assert_eq!(texture.levels()[1].get_pixel(0, 0).0[0], 128);
+    assert!((red(&texture, 0.25, 0.0) - 0.0).abs() < 1e-3);
+    assert!((red(&texture, 0.25, 0.5) - 64.0).abs() < 1e-3);
+    assert!((red(&texture, 0.25, 1.0) - 128.0).abs() < 1e-3);
+    assert!((red(&texture, 0.25, 7.0) - 128.0).abs() < 1e-3);
+
+    texture.filter = Filter::Bilinear;
+    assert!((red(&texture, 0.25, 0.4) - 0.0).abs() < 1e-3);
+    assert!((red(&texture, 0.25, 0.6) - 128.0).abs() < 1e-3);
+}
+
+/// [returns] a square facing +z, from -1 to 1, with its texture repeated `repeats` times across.
+fn quad(repeats: f32) -> Mesh {
+    let corners = [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)];
+    let face = |vertices: [usize; 3]| FaceInfo { vertices, color: None, part: 0, normals: None, uvs: Some(vertices) };
+
+    Mesh {
+        faces: vec![face([0, 1, 2]), face([0, 2, 3])],
+        vertices: corners.iter().enumerate().map(|(i, &(x, y))| VertexInfo {
+            vtx: VertexModel(Pt3::new(x, y, 0.0)),
+            faces: if i % 2 == 0 { vec![0, 1] } else { vec![i / 2] },
+            color: None
+        }).collect(),
+        parts: vec!["quad".to_string()],
+        normals: vec![],
+        uvs: corners.iter().map(|&(x, y)| Pt2::new((x + 1.0) / 2.0 * repeats, (y + 1.0) / 2.0 * repeats)).collect()
+    }
+}
+
+/// [returns] the red channel of the pixels covered by a far-away checkerboard, filtered by `filter`.
+fn render_checkerboard(filter: Filter) -> Vec<u8> {
+    let mut texture = Texture2D::new(RgbaImage::from_fn(2, 2, |x, y| Rgba(if (x + y) % 2 == 0 { [255; 4] } else { [0, 0, 0, 255] })));
+    texture.filter = filter;
+
+    let model = Model {
+        mesh: Arc::new(quad(32.0)),
+        transform: na::Similarity3::identity(),
+        texture: common::matcap(),
+        base_color: Some(Arc::new(texture)),
+        lods: vec![]
+    };
+
+    let camera = CameraInfo::new(Pt3::new(0.0, 0.0, 10.0), Pt3::origin());
+    let proj = na::Perspective3::new(1.0, 1.0, 0.1, 100.0);
+    let mut surface = Surface::new(64, 64, false);
+    grr::render_model(&model, &camera, &proj, &mut surface);
+
+    // Away from the edges of the quad, which are only partly covered.
+    let img = surface.to_img();
+    (28..36).flat_map(|y| (28..36).map(move |x| (x, y))).map(|(x, y)| img.get_pixel(x, y)[0]).collect()
+}
+
+#[test]
+fn test_texture_render_minified() {
+    // [Scenario] the nearest texels alias into black and white
+    let nearest = render_checkerboard(Filter::Nearest);
+    assert!(nearest.contains(&0));
+    assert!(nearest.iter().any(|&r| r > 100));
+
+    // [Scenario] the mipmaps average the checkerboard out to an even grey
+    let trilinear = render_checkerboard(Filter::Trilinear);
+    let (min, max) = (trilinear.iter().min().unwrap(), trilinear.iter().max().unwrap());
+    assert!(max - min <= 2 && *min > 0, "{} to {}", min, max);
+}
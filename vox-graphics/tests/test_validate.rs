@@ -0,0 +1,151 @@
+use std::path::Path;
+
+use vox_graphics::model::{FaceInfo, Mesh};
+use vox_stl::stl;
+
+fn cube() -> Mesh {
+    Mesh::from_facets(stl::parse_from_file(Path::new("../resources/models/ascii-cube.stl")).unwrap())
+}
+
+fn face(vertices: [usize; 3]) -> FaceInfo {
+    FaceInfo { vertices, color: None, part: 0, normals: None, uvs: None }
+}
+
+#[test]
+fn test_validate_clean_mesh() {
+    let report = cube().validate();
+
+    assert!(report.is_watertight(), "{:?}", report);
+    assert_eq!(report.components.len(), 1);
+
+    let sphere = Mesh::from_facets(stl::parse_from_file(Path::new("../resources/models/ascii-sphere.stl")).unwrap());
+    assert!(sphere.validate().is_watertight());
+}
+
+#[test]
+fn test_validate_problems() {
+    // [Scenario] a missing face leaves a triangular hole
+    let mut mesh = cube();
+    let removed = mesh.faces.remove(3);
+    let report = mesh.validate();
+    assert_eq!(report.boundary_loops.len(), 1);
+    assert_eq!(report.boundary_loops[0].len(), 3);
+    assert!(removed.vertices.iter().all(|v| report.boundary_loops[0].contains(v)));
+    assert!(!report.is_watertight());
+
+    // [Scenario] two holes that touch at a single vertex are told apart
+    let mut mesh = bow_tie_holes();
+    let report = mesh.validate();
+    assert_eq!(report.boundary_loops.iter().map(Vec::len).collect::<Vec<_>>(), vec![3, 3]);
+    assert!(report.open_boundaries.is_empty());
+
+    // [Scenario] a face on the rim of a hole wound the wrong way around leaves a chain that doesn't
+    // close
+    let rim = mesh.faces.iter().position(|f| report.boundary_loops[0].iter().filter(|v| f.vertices.contains(v)).count() == 2).unwrap();
+    mesh.faces[rim].vertices.swap(1, 2);
+    let report = mesh.validate();
+    assert!(!report.open_boundaries.is_empty());
+    assert!(!report.is_watertight());
+
+    // [Scenario] a face wound the wrong way around
+    let mut mesh = cube();
+    mesh.faces[5].vertices.swap(0, 1);
+    let report = mesh.validate();
+    assert_eq!(report.inconsistent_edges.len(), 3);
+    assert!(report.boundary_loops.is_empty());
+
+    // [Scenario] degenerate and duplicate faces
+    let mut mesh = cube();
+    let [a, b, c] = mesh.faces[0].vertices;
+    mesh.faces.push(face([a, a, b]));
+    mesh.faces.push(face([c, a, b]));
+    let report = mesh.validate();
+    assert_eq!(report.degenerate_faces, vec![12]);
+    assert_eq!(report.duplicate_faces, vec![13]);
+
+    // The duplicate is a third face on each of its edges.
+    assert_eq!(report.non_manifold_edges.len(), 3);
+
+    // [Scenario] a zero-area face whose vertices are distinct (but in a line)
+    let mut mesh = cube();
+    let [p0, p1] = [mesh.vertices[a].vtx, mesh.vertices[b].vtx];
+    mesh.vertices.push(mesh.vertices[a].clone());
+    mesh.vertices.last_mut().unwrap().vtx.0 = nalgebra::center(&p0.0, &p1.0);
+    mesh.faces.push(face([a, b, mesh.vertices.len() - 1]));
+    assert_eq!(mesh.validate().degenerate_faces, vec![12]);
+
+    // [Scenario] two separate cubes
+    let mut mesh = cube();
+    let offset = mesh.vertices.len();
+    mesh.vertices.extend(cube().vertices);
+    mesh.faces.extend(cube().faces.iter().map(|f| face(f.vertices.map(|v| v + offset))));
+    let report = mesh.validate();
+    assert_eq!(report.components.len(), 2);
+    assert_eq!(report.components[1].len(), 12);
+    assert!(report.is_watertight());
+}
+
+#[test]
+fn test_repair() {
+    let mut mesh = cube();
+    let original = mesh.faces.clone();
+
+    // Break the cube in every way that can be fixed: turn it inside out, flip one face back (so
+    // the winding is inconsistent), punch a hole, and add a degenerate and a duplicate face.
+    mesh.faces.iter_mut().for_each(|f| f.vertices.swap(1, 2));
+    mesh.faces[4].vertices.swap(1, 2);
+    mesh.faces.remove(7);
+    let [a, b, c] = mesh.faces[0].vertices;
+    mesh.faces.push(face([a, b, b]));
+    mesh.faces.push(face([b, c, a]));
+
+    let report = mesh.repair(8);
+
+    assert_eq!(report.degenerate_faces_removed, 1);
+    assert_eq!(report.duplicate_faces_removed, 1);
+    assert_eq!(report.holes_filled, 1);
+    assert_eq!(report.faces_added, 1);
+    assert_eq!(report.faces_flipped, 10);
+    assert!(report.remaining.is_watertight(), "{:?}", report.remaining);
+
+    // The cube is back to its outward winding.
+    let mut expected : Vec<[usize; 3]> = original.iter().map(|f| sorted_rotation(f.vertices)).collect();
+    let mut actual : Vec<[usize; 3]> = mesh.faces.iter().map(|f| sorted_rotation(f.vertices)).collect();
+    expected.sort();
+    actual.sort();
+    assert_eq!(actual, expected);
+
+    // Every vertex knows its faces again.
+    assert_eq!(mesh.vertices.iter().map(|v| v.faces.len()).sum::<usize>(), 36);
+
+    // [Scenario] holes bigger than the limit are left alone
+    let mut mesh = cube();
+    mesh.faces.truncate(10);
+    let report = mesh.repair(3);
+    assert_eq!(report.holes_filled, 0);
+    assert_eq!(report.remaining.boundary_loops.len(), 1);
+
+    // [Scenario] holes that touch at a vertex are filled one at a time
+    let mut mesh = bow_tie_holes();
+    let report = mesh.repair(8);
+    assert_eq!(report.holes_filled, 2);
+    assert_eq!(report.faces_added, 2);
+    assert!(report.remaining.is_watertight(), "{:?}", report.remaining);
+}
+
+/// [returns] the cube with two faces missing that only share a vertex (so the holes touch there).
+fn bow_tie_holes() -> Mesh {
+    let mut mesh = cube();
+    let shared = |i: usize, j: usize| mesh.faces[i].vertices.iter().filter(|v| mesh.faces[j].vertices.contains(v)).count();
+    let (i, j) = (0..12).flat_map(|i| (i + 1..12).map(move |j| (i, j))).find(|&(i, j)| shared(i, j) == 1).unwrap();
+
+    mesh.faces.remove(j);
+    mesh.faces.remove(i);
+    mesh
+}
+
+/// [returns] the face rotated to start at its lowest vertex (which keeps the winding).
+fn sorted_rotation(v: [usize; 3]) -> [usize; 3] {
+    let i = (0..3).min_by_key(|&i| v[i]).unwrap();
+    [v[i], v[(i + 1) % 3], v[(i + 2) % 3]]
+}
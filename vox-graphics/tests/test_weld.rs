@@ -0,0 +1,73 @@
+use std::path::Path;
+
+use vox_graphics::model::Mesh;
+use vox_stl::fwd::Facet;
+use vox_stl::stl;
+
+/// [returns] the facets of `path`, with every corner nudged by up to `noise` (deterministically).
+fn noisy_facets(path: &str, noise: f32) -> Vec<Facet> {
+    let mut facets = stl::parse_from_file(Path::new(path)).unwrap();
+    let mut seed = 1u32;
+
+    for facet in &mut facets {
+        for vertex in &mut facet.tri {
+            for c in &mut vertex.0 {
+                seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+                *c += noise * ((seed >> 16) as f32 / 32768.0 - 1.0);
+            }
+        }
+    }
+
+    facets
+}
+
+#[test]
+fn test_weld_noisy_mesh() {
+    let path = "../resources/models/ascii-sphere.stl";
+    let clean = Mesh::from_facets(stl::parse_from_file(Path::new(path)).unwrap());
+
+    // Without welding, none of the corners meet.
+    let cracked = Mesh::from_facets(noisy_facets(path, 1e-5));
+    assert_eq!(cracked.vertices.len(), 3 * 960);
+    assert!(!cracked.validate().is_watertight());
+
+    let (welded, report) = Mesh::from_facets_welded(noisy_facets(path, 1e-5), 1e-4);
+    assert_eq!(welded.vertices.len(), clean.vertices.len());
+    assert_eq!(report.vertices_merged, 3 * 960 - clean.vertices.len());
+    assert_eq!(report.faces_collapsed, 0);
+    assert!(welded.validate().is_watertight());
+
+    // Every vertex knows its faces.
+    assert_eq!(welded.vertices.iter().map(|v| v.faces.len()).sum::<usize>(), 3 * 960);
+}
+
+#[test]
+fn test_weld_collapses_faces() {
+    // [Scenario] an epsilon as big as the cube merges everything, and every face collapses
+    let mut mesh = Mesh::from_facets(stl::parse_from_file(Path::new("../resources/models/ascii-cube.stl")).unwrap());
+    let report = mesh.weld(10.0);
+    assert_eq!(report.vertices_merged, 7);
+    assert_eq!(report.faces_collapsed, 12);
+    assert!(mesh.faces.is_empty());
+
+    // [Scenario] an epsilon of zero only merges identical positions (including -0.0 and 0.0)
+    let (mut mesh, report) = Mesh::from_facets_welded(stl::parse_from_file(Path::new("../resources/models/ascii-cube.stl")).unwrap(), 0.0);
+    assert_eq!(report.vertices_merged, 0);
+    assert_eq!(mesh.vertices.len(), 8);
+
+    let mut split = mesh.clone();
+    let v = split.faces[0].vertices[0];
+    split.vertices.push(split.vertices[v].clone());
+    split.vertices.last_mut().unwrap().vtx.0 = split.vertices[v].vtx.0.map(|c| if c == 0.0 { -0.0 } else { c });
+    split.faces[0].vertices[0] = 8;
+
+    let mut nudged = split.clone();
+    nudged.vertices[8].vtx.0.x += 1e-6;
+
+    assert_eq!(split.weld(0.0).vertices_merged, 1);
+    assert_eq!(split.faces[0].vertices, mesh.faces[0].vertices);
+    assert_eq!(nudged.weld(0.0).vertices_merged, 0);
+
+    // Welding an already clean mesh changes nothing.
+    assert_eq!(mesh.weld(1e-3).vertices_merged, 0);
+}
@@ -0,0 +1,55 @@
+use std::fmt;
+use std::io;
+
+/// The ways in which reading an OBJ file can fail.
+///
+/// Line and column numbers are 1-based so they can be reported to users as-is.
+///
+#[derive(Debug)]
+pub enum ObjError {
+    /// The underlying reader failed (this includes invalid UTF-8).
+    Io(io::Error),
+
+    /// A token that should have been a number could not be parsed as one.
+    BadNumber { line: usize, column: usize, token: String },
+
+    /// A face refers to an element that doesn't exist (yet), or uses index 0.
+    BadIndex { line: usize, column: usize, token: String },
+
+    /// A statement doesn't have the number of arguments it needs.
+    MissingArguments { line: usize, statement: String }
+}
+
+impl fmt::Display for ObjError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ObjError::Io(why) => {
+                write!(f, "i/o error: {}", why)
+            }
+            ObjError::BadNumber { line, column, token } => {
+                write!(f, "[{}:{}] expected a number, got '{}'", line, column, token)
+            }
+            ObjError::BadIndex { line, column, token } => {
+                write!(f, "[{}:{}] invalid index '{}'", line, column, token)
+            }
+            ObjError::MissingArguments { line, statement } => {
+                write!(f, "[{}] too few arguments for '{}'", line, statement)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ObjError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ObjError::Io(why) => Some(why),
+            _ => None
+        }
+    }
+}
+
+impl From<io::Error> for ObjError {
+    fn from(why: io::Error) -> Self {
+        ObjError::Io(why)
+    }
+}
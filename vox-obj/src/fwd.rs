@@ -0,0 +1,45 @@
+/// A corner of a face, made up of indices into `ObjData`. Indices are 0-based and have already
+/// been resolved (i.e. negative indices are made absolute) and bounds-checked.
+#[derive(Default, Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FaceVertex {
+    pub position: usize,
+    pub uv: Option<usize>,
+    pub normal: Option<usize>
+}
+
+/// A polygonal face, with 3 or more corners.
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct Face {
+    pub vertices: Vec<FaceVertex>,
+
+    // Indices into `ObjData::groups` and `ObjData::objects`.
+    pub group: usize,
+    pub object: usize
+}
+
+/// The geometry of an OBJ file.
+///
+/// Faces that appear before any 'g' or 'o' statement belong to the unnamed group/object "".
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct ObjData {
+    pub positions: Vec<[f32; 3]>,
+    pub uvs: Vec<[f32; 2]>,
+    pub normals: Vec<[f32; 3]>,
+    pub faces: Vec<Face>,
+    pub groups: Vec<String>,
+    pub objects: Vec<String>
+}
+
+impl Default for ObjData {
+    fn default() -> Self {
+        Self {
+            positions: vec![],
+            uvs: vec![],
+            normals: vec![],
+            faces: vec![],
+            groups: vec![String::new()],
+            objects: vec![String::new()]
+        }
+    }
+}
@@ -0,0 +1,28 @@
+pub mod error;
+pub mod fwd;
+pub mod triangulate;
+
+mod parser;
+
+pub mod obj {
+    use std::fs::File;
+    use std::io::{BufReader, Read};
+    use std::path::Path;
+
+    pub use crate::error::ObjError;
+
+    use crate::fwd::ObjData;
+    use crate::parser;
+
+    pub fn parse_from_file(path: &Path) -> Result<ObjData, ObjError> {
+        parse_from_reader(File::open(path)?)
+    }
+
+    pub fn parse_from_reader<R: Read>(reader: R) -> Result<ObjData, ObjError> {
+        parser::obj_from_reader(BufReader::new(reader))
+    }
+
+    pub fn parse_from_bytes(bytes: &[u8]) -> Result<ObjData, ObjError> {
+        parser::obj_from_reader(bytes)
+    }
+}
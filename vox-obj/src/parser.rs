@@ -0,0 +1,207 @@
+use std::collections::HashMap;
+use std::io::BufRead;
+
+use crate::error::ObjError;
+use crate::fwd::{Face, FaceVertex, ObjData};
+
+/// [returns] the 1-based column at which `token` starts within `line`.
+///
+/// [note]    `token` must be a sub-slice of `line`.
+fn column_of(line: &str, token: &str) -> usize {
+    token.as_ptr() as usize - line.as_ptr() as usize + 1
+}
+
+/// Keeps track of the state that spans lines, i.e. the current group and object.
+struct Parser {
+    data: ObjData,
+    group: usize,
+    object: usize,
+    group_lookup: HashMap<String, usize>,
+    object_lookup: HashMap<String, usize>
+}
+
+impl Parser {
+    fn new() -> Self {
+        Self {
+            data: ObjData::default(),
+            group: 0,
+            object: 0,
+            group_lookup: HashMap::from([(String::new(), 0)]),
+            object_lookup: HashMap::from([(String::new(), 0)])
+        }
+    }
+
+    /// [returns] the first `N` arguments of a statement parsed as floats, with `defaults` used
+    ///           for any optional trailing arguments that are missing.
+    fn parse_floats<const N: usize>(
+        line: &str,
+        line_no: usize,
+        args: &[&str],
+        required: usize,
+        defaults: [f32; N]
+    )
+        -> Result<[f32; N], ObjError>
+    {
+        if args.len() < required {
+            return Err(ObjError::MissingArguments { line: line_no, statement: line.trim().to_string() });
+        }
+
+        let mut values = defaults;
+        for (value, token) in values.iter_mut().zip(args) {
+            *value = token.parse::<f32>().map_err(|_| ObjError::BadNumber {
+                line: line_no,
+                column: column_of(line, token),
+                token: token.to_string()
+            })?;
+        }
+
+        Ok(values)
+    }
+
+    /// [returns] the 0-based index for a 1-based (or negative, relative) OBJ index.
+    fn resolve_index(line: &str, line_no: usize, token: &str, count: usize) -> Result<usize, ObjError> {
+        let bad_index = || ObjError::BadIndex {
+            line: line_no,
+            column: column_of(line, token),
+            token: token.to_string()
+        };
+
+        let i = token.parse::<i64>().map_err(|_| bad_index())?;
+
+        if i > 0 && i as u64 <= count as u64 {
+            Ok(i as usize - 1)
+        } else if i < 0 && i.unsigned_abs() <= count as u64 {
+            Ok(count - i.unsigned_abs() as usize)
+        } else {
+            Err(bad_index())
+        }
+    }
+
+    /// Parses a face corner of the form 'v', 'v/vt', 'v//vn' or 'v/vt/vn'.
+    fn parse_face_vertex(&self, line: &str, line_no: usize, token: &str) -> Result<FaceVertex, ObjError> {
+        let mut parts = token.split('/');
+
+        let position = Self::resolve_index(line, line_no, parts.next().unwrap_or(""), self.data.positions.len())?;
+
+        let mut optional = |count: usize| -> Result<Option<usize>, ObjError> {
+            match parts.next() {
+                None | Some("") => Ok(None),
+                Some(t) => Self::resolve_index(line, line_no, t, count).map(Some)
+            }
+        };
+
+        let uv = optional(self.data.uvs.len())?;
+        let normal = optional(self.data.normals.len())?;
+
+        Ok(FaceVertex { position, uv, normal })
+    }
+
+    fn lookup(names: &mut Vec<String>, lookup: &mut HashMap<String, usize>, name: &str) -> usize {
+        *lookup.entry(name.to_string()).or_insert_with(|| {
+            names.push(name.to_string());
+            names.len() - 1
+        })
+    }
+
+    fn parse_line(&mut self, line: &str, line_no: usize) -> Result<(), ObjError> {
+        // Everything after a '#' is a comment.
+        let content = match line.find('#') {
+            Some(i) => &line[..i],
+            None => line
+        };
+
+        let mut tokens = content.split_whitespace();
+
+        let statement = match tokens.next() {
+            Some(s) => s,
+            None => return Ok(())
+        };
+
+        let args : Vec<&str> = tokens.collect();
+
+        match statement {
+            "v" => {
+                let p = Self::parse_floats(line, line_no, &args, 3, [0.0; 3])?;
+                self.data.positions.push(p);
+            }
+
+            "vt" => {
+                let uv = Self::parse_floats(line, line_no, &args, 1, [0.0; 2])?;
+                self.data.uvs.push(uv);
+            }
+
+            "vn" => {
+                let n = Self::parse_floats(line, line_no, &args, 3, [0.0; 3])?;
+                self.data.normals.push(n);
+            }
+
+            "f" => {
+                if args.len() < 3 {
+                    return Err(ObjError::MissingArguments { line: line_no, statement: line.trim().to_string() });
+                }
+
+                let vertices = args.iter()
+                    .map(|token| self.parse_face_vertex(line, line_no, token))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                self.data.faces.push(Face { vertices, group: self.group, object: self.object });
+            }
+
+            "g" => {
+                let name = args.join(" ");
+                self.group = Self::lookup(&mut self.data.groups, &mut self.group_lookup, &name);
+            }
+
+            "o" => {
+                let name = args.join(" ");
+                self.object = Self::lookup(&mut self.data.objects, &mut self.object_lookup, &name);
+            }
+
+            // Materials, smoothing groups, lines, points, free-form geometry, etc. are ignored.
+            _ => {}
+        }
+
+        Ok(())
+    }
+}
+
+/// A simple parser for Wavefront OBJ files.
+///
+/// Only polygonal geometry is read: vertices (v), texture coordinates (vt), normals (vn), faces (f)
+/// and the groups (g) and objects (o) they belong to.
+///
+/// see: https://en.wikipedia.org/wiki/Wavefront_.obj_file
+///
+pub fn obj_from_reader<R: BufRead>(reader: R) -> Result<ObjData, ObjError> {
+    let mut parser = Parser::new();
+
+    // A trailing '\' continues a statement onto the next line.
+    let mut pending = String::new();
+    let mut pending_line_no = 0;
+
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+
+        if pending.is_empty() {
+            pending_line_no = i + 1;
+        }
+
+        match line.strip_suffix('\\') {
+            Some(start) => {
+                pending.push_str(start);
+                pending.push(' ');
+            }
+            None => {
+                pending.push_str(&line);
+                parser.parse_line(&pending, pending_line_no)?;
+                pending.clear();
+            }
+        }
+    }
+
+    if !pending.is_empty() {
+        parser.parse_line(&pending, pending_line_no)?;
+    }
+
+    Ok(parser.data)
+}
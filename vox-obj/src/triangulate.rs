@@ -0,0 +1,105 @@
+use crate::fwd::{FaceVertex, ObjData};
+
+/// [returns] the z component of (b - a) x (c - b) for 2D points.
+fn turn(a: [f32; 2], b: [f32; 2], c: [f32; 2]) -> f32 {
+    (b[0] - a[0]) * (c[1] - b[1]) - (b[1] - a[1]) * (c[0] - b[0])
+}
+
+fn inside_triangle(p: [f32; 2], a: [f32; 2], b: [f32; 2], c: [f32; 2]) -> bool {
+    let d0 = turn(a, b, p);
+    let d1 = turn(b, c, p);
+    let d2 = turn(c, a, p);
+    (d0 >= 0.0 && d1 >= 0.0 && d2 >= 0.0) || (d0 <= 0.0 && d1 <= 0.0 && d2 <= 0.0)
+}
+
+/// Splits a (possibly concave) planar polygon into triangles by ear clipping.
+///
+/// The polygon is projected onto the axis plane that best preserves its shape, and the winding of
+/// the input is kept. Degenerate polygons fall back to a simple fan.
+///
+/// [returns] triangles as indices into `polygon`.
+pub fn triangulate_polygon(polygon: &[[f32; 3]]) -> Vec<[usize; 3]> {
+    let n = polygon.len();
+    if n < 3 {
+        return vec![];
+    } else if n == 3 {
+        return vec![[0, 1, 2]];
+    }
+
+    // Newell's method gives a robust normal even for concave polygons.
+    let mut normal = [0.0f32; 3];
+    for i in 0..n {
+        let a = polygon[i];
+        let b = polygon[(i + 1) % n];
+        normal[0] += (a[1] - b[1]) * (a[2] + b[2]);
+        normal[1] += (a[2] - b[2]) * (a[0] + b[0]);
+        normal[2] += (a[0] - b[0]) * (a[1] + b[1]);
+    }
+
+    let fan = || (1..n - 1).map(|i| [0, i, i + 1]).collect();
+
+    // Drop the axis along which the normal is largest.
+    let axis = (0..3).max_by(|&a, &b| normal[a].abs().total_cmp(&normal[b].abs())).unwrap();
+    if normal[axis] == 0.0 {
+        return fan();
+    }
+
+    let (u, v) = match axis { 0 => (1, 2), 1 => (2, 0), _ => (0, 1) };
+    let flat : Vec<[f32; 2]> = polygon.iter().map(|p| [p[u], p[v]]).collect();
+
+    // Convex corners turn the same way as the polygon as a whole.
+    let orientation = normal[axis].signum();
+
+    let mut remaining : Vec<usize> = (0..n).collect();
+    let mut triangles = Vec::with_capacity(n - 2);
+
+    while remaining.len() > 3 {
+        let m = remaining.len();
+
+        let ear = (0..m).find(|&i| {
+            let (a, b, c) = (remaining[(i + m - 1) % m], remaining[i], remaining[(i + 1) % m]);
+
+            if turn(flat[a], flat[b], flat[c]) * orientation <= 0.0 {
+                return false;
+            }
+
+            !remaining.iter()
+                .filter(|&&p| p != a && p != b && p != c)
+                .any(|&p| inside_triangle(flat[p], flat[a], flat[b], flat[c]))
+        });
+
+        match ear {
+            Some(i) => {
+                triangles.push([remaining[(i + m - 1) % m], remaining[i], remaining[(i + 1) % m]]);
+                remaining.remove(i);
+            }
+            None => {
+                // Self-intersecting or collinear leftovers, so fan whatever is left.
+                for i in 1..m - 1 {
+                    triangles.push([remaining[0], remaining[i], remaining[i + 1]]);
+                }
+                return triangles;
+            }
+        }
+    }
+
+    triangles.push([remaining[0], remaining[1], remaining[2]]);
+    triangles
+}
+
+impl ObjData {
+    /// [returns] every face split into triangles, along with the index of the face it came from.
+    pub fn triangles(&self) -> Vec<(usize, [FaceVertex; 3])> {
+        let mut triangles = vec![];
+
+        for (face_i, face) in self.faces.iter().enumerate() {
+            let polygon : Vec<[f32; 3]> = face.vertices.iter().map(|v| self.positions[v.position]).collect();
+
+            for [a, b, c] in triangulate_polygon(&polygon) {
+                triangles.push((face_i, [face.vertices[a], face.vertices[b], face.vertices[c]]));
+            }
+        }
+
+        triangles
+    }
+}
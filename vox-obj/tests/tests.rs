@@ -0,0 +1,85 @@
+use std::path::Path;
+
+use vox_obj::fwd::FaceVertex;
+use vox_obj::obj;
+use vox_obj::triangulate::triangulate_polygon;
+
+#[test]
+fn parse_obj() {
+    let data = obj::parse_from_file(Path::new("../resources/models/cube-quads.obj")).unwrap();
+
+    assert_eq!(data.positions.len(), 8);
+    assert_eq!(data.uvs.len(), 4);
+    assert_eq!(data.normals.len(), 6);
+    assert_eq!(data.faces.len(), 6);
+
+    assert_eq!(data.objects, vec!["".to_string(), "cube".to_string()]);
+    assert_eq!(data.groups, vec!["".to_string(), "sides".to_string(), "caps".to_string()]);
+    assert!(data.faces.iter().all(|f| f.object == 1));
+    assert_eq!(data.faces.iter().filter(|f| f.group == 1).count(), 4);
+
+    // The relative indices (continued onto a second line) resolve to the last 4 of each.
+    assert_eq!(data.faces[5].vertices, vec![
+        FaceVertex { position: 4, uv: Some(0), normal: Some(1) },
+        FaceVertex { position: 5, uv: Some(1), normal: Some(1) },
+        FaceVertex { position: 6, uv: Some(2), normal: Some(1) },
+        FaceVertex { position: 7, uv: Some(3), normal: Some(1) },
+    ]);
+
+    assert_eq!(data.triangles().len(), 12);
+}
+
+#[test]
+fn parse_obj_face_formats() {
+    let src = "v 0 0 0\nv 1 0 0\nv 0 1 0\nvn 0 0 1\nvt 0.5\nf 1 2 3\nf 1//1 2//1 3//1\nf 1/1 2/1 3/1\n";
+    let data = obj::parse_from_bytes(src.as_bytes()).unwrap();
+
+    assert_eq!(data.faces[0].vertices[0], FaceVertex { position: 0, uv: None, normal: None });
+    assert_eq!(data.faces[1].vertices[1], FaceVertex { position: 1, uv: None, normal: Some(0) });
+    assert_eq!(data.faces[2].vertices[2], FaceVertex { position: 2, uv: Some(0), normal: None });
+    assert_eq!(data.uvs[0], [0.5, 0.0]);
+}
+
+#[test]
+fn parse_malformed_obj() {
+    // [Scenario] index 0 isn't valid
+    match obj::parse_from_bytes(b"v 0 0 0\nv 1 0 0\nv 0 1 0\nf 0 1 2\n") {
+        Err(obj::ObjError::BadIndex { line, column, token }) => {
+            assert_eq!((line, column, token.as_str()), (4, 3, "0"));
+        }
+        other => panic!("expected BadIndex, got {:?}", other)
+    }
+
+    // [Scenario] referring to a vertex that hasn't been declared yet
+    assert!(matches!(obj::parse_from_bytes(b"v 0 0 0\nf 1 2 -3\n"), Err(obj::ObjError::BadIndex { line: 2, .. })));
+
+    // [Scenario] a coordinate that isn't a number
+    assert!(matches!(obj::parse_from_bytes(b"v 0 x 0\n"), Err(obj::ObjError::BadNumber { line: 1, column: 5, .. })));
+
+    // [Scenario] a face with too few corners
+    assert!(matches!(obj::parse_from_bytes(b"v 0 0 0\nf 1 1\n"), Err(obj::ObjError::MissingArguments { line: 2, .. })));
+}
+
+#[test]
+fn triangulate_concave_polygon() {
+    // An 'L' shape, whose only valid triangulations avoid the reflex corner at index 3.
+    let polygon = [
+        [0.0, 0.0, 0.0],
+        [2.0, 0.0, 0.0],
+        [2.0, 1.0, 0.0],
+        [1.0, 1.0, 0.0],
+        [1.0, 2.0, 0.0],
+        [0.0, 2.0, 0.0],
+    ];
+
+    let triangles = triangulate_polygon(&polygon);
+    assert_eq!(triangles.len(), 4);
+
+    // The triangles keep the (counter-clockwise) winding, and cover the polygon exactly (area 3).
+    let areas : Vec<f32> = triangles.iter().map(|[a, b, c]| {
+        let (a, b, c) = (polygon[*a], polygon[*b], polygon[*c]);
+        0.5 * ((b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0]))
+    }).collect();
+    assert!(areas.iter().all(|&a| a > 0.0));
+    assert_eq!(areas.iter().sum::<f32>(), 3.0);
+}
@@ -0,0 +1,67 @@
+use std::fmt;
+use std::io;
+
+/// The ways in which reading (or writing) an OFF file can fail.
+///
+/// Line numbers are 1-based so they can be reported to users as-is.
+///
+#[derive(Debug)]
+pub enum OffError {
+    /// The underlying reader failed (this includes invalid UTF-8).
+    Io(io::Error),
+
+    /// The file doesn't start with an OFF keyword (i.e. 'OFF', 'COFF', 'NOFF' or 'CNOFF').
+    BadHeader { line: usize, token: String },
+
+    /// A token that should have been a number could not be parsed as one.
+    BadNumber { line: usize, token: String },
+
+    /// A face refers to a vertex that doesn't exist.
+    BadIndex { line: usize, index: usize },
+
+    /// The file ended before all of the declared vertices and faces were read.
+    UnexpectedEof { line: usize },
+
+    /// Data being written has per-vertex values (i.e. normals) for some but not all of the vertices.
+    BadLength { field: &'static str, len: usize, expected: usize }
+}
+
+impl fmt::Display for OffError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OffError::Io(why) => {
+                write!(f, "i/o error: {}", why)
+            }
+            OffError::BadHeader { line, token } => {
+                write!(f, "[{}] expected an OFF header, got '{}'", line, token)
+            }
+            OffError::BadNumber { line, token } => {
+                write!(f, "[{}] expected a number, got '{}'", line, token)
+            }
+            OffError::BadIndex { line, index } => {
+                write!(f, "[{}] vertex index {} is out of range", line, index)
+            }
+            OffError::UnexpectedEof { line } => {
+                write!(f, "[{}] unexpected end of file", line)
+            }
+            OffError::BadLength { field, len, expected } => {
+                write!(f, "{} has {} entries, but there are {} vertices", field, len, expected)
+            }
+        }
+    }
+}
+
+impl std::error::Error for OffError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            OffError::Io(why) => Some(why),
+            _ => None
+        }
+    }
+}
+
+impl From<io::Error> for OffError {
+    fn from(why: io::Error) -> Self {
+        OffError::Io(why)
+    }
+}
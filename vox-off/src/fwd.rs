@@ -0,0 +1,17 @@
+/// The contents of an OFF file.
+///
+/// Optional per-vertex data (from the N and C header prefixes) is either empty or has one entry per
+/// vertex. Faces are polygons, as indices into `positions`.
+///
+/// see: https://shape.cs.princeton.edu/benchmark/documentation/off_format.html
+///
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct OffData {
+    pub positions: Vec<[f32; 3]>,
+    pub normals: Vec<[f32; 3]>,
+    pub vertex_colors: Vec<[u8; 4]>,
+    pub faces: Vec<Vec<usize>>,
+
+    /// Either empty or has one entry per face, for files where some faces are coloured.
+    pub face_colors: Vec<Option<[u8; 4]>>
+}
@@ -0,0 +1,35 @@
+pub mod error;
+pub mod fwd;
+
+mod parser;
+mod writer;
+
+pub mod off {
+    use std::fs::File;
+    use std::io::{BufReader, Read, Write};
+    use std::path::Path;
+
+    pub use crate::error::OffError;
+
+    use crate::fwd::OffData;
+    use crate::parser;
+    use crate::writer;
+
+    pub fn parse_from_file(path: &Path) -> Result<OffData, OffError> {
+        parse_from_reader(File::open(path)?)
+    }
+
+    pub fn parse_from_reader<R: Read>(reader: R) -> Result<OffData, OffError> {
+        parser::off_from_reader(BufReader::new(reader))
+    }
+
+    pub fn parse_from_bytes(bytes: &[u8]) -> Result<OffData, OffError> {
+        parser::off_from_reader(bytes)
+    }
+
+    /// The output parses back to exactly the same data.
+    ///
+    pub fn write<W: Write>(mut writer: W, data: &OffData) -> Result<(), OffError> {
+        writer::write_off(&mut writer, data)
+    }
+}
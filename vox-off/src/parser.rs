@@ -0,0 +1,145 @@
+use std::io::BufRead;
+use std::str::FromStr;
+
+use crate::error::OffError;
+use crate::fwd::OffData;
+
+/// The non-empty lines of the file with comments stripped, split into tokens.
+struct Lines<R> {
+    reader: R,
+    line: String,
+    line_no: usize
+}
+
+impl<R: BufRead> Lines<R> {
+    /// [returns] the tokens of the next line that has any, or None at the end of the file.
+    fn next_tokens(&mut self) -> Result<Option<Vec<String>>, OffError> {
+        loop {
+            self.line.clear();
+            if self.reader.read_line(&mut self.line)? == 0 {
+                return Ok(None);
+            }
+            self.line_no += 1;
+
+            let content = self.line.split('#').next().unwrap_or("");
+            let tokens : Vec<String> = content.split_whitespace().map(str::to_string).collect();
+
+            if !tokens.is_empty() {
+                return Ok(Some(tokens));
+            }
+        }
+    }
+
+    fn expect_tokens(&mut self) -> Result<Vec<String>, OffError> {
+        self.next_tokens()?.ok_or(OffError::UnexpectedEof { line: self.line_no })
+    }
+
+    fn parse<T: FromStr>(&self, token: &str) -> Result<T, OffError> {
+        token.parse::<T>().map_err(|_| OffError::BadNumber { line: self.line_no, token: token.to_string() })
+    }
+
+    /// [returns] the colour in `tokens`, which is either 3 or 4 integers in [0, 255] or floats in
+    ///           [0, 1] (alpha defaults to opaque).
+    fn parse_color(&self, tokens: &[String]) -> Result<[u8; 4], OffError> {
+        let is_float = tokens.iter().any(|t| t.contains('.') || t.contains('e') || t.contains('E'));
+
+        let mut rgba = [255; 4];
+        for (c, token) in rgba.iter_mut().zip(tokens) {
+            let v : f32 = self.parse(token)?;
+            *c = if is_float { (v * 255.0).round().clamp(0.0, 255.0) as u8 } else { v.clamp(0.0, 255.0) as u8 };
+        }
+
+        Ok(rgba)
+    }
+}
+
+/// A simple parser for (ascii) OFF files, including the common C (colour) and N (normal) variants.
+///
+/// Faces may be followed by a colour, as written by Geomview. Colour map indices aren't supported.
+///
+/// see: https://shape.cs.princeton.edu/benchmark/documentation/off_format.html
+///
+pub fn off_from_reader<R: BufRead>(reader: R) -> Result<OffData, OffError> {
+    let mut lines = Lines { reader, line: String::new(), line_no: 0 };
+    let mut data = OffData::default();
+
+    let mut tokens = lines.expect_tokens()?;
+
+    // The keyword may share its line with the counts.
+    let keyword = tokens.remove(0);
+    let (has_normals, has_colors) = match keyword.as_str() {
+        "OFF" => (false, false),
+        "NOFF" => (true, false),
+        "COFF" => (false, true),
+        "CNOFF" | "NCOFF" => (true, true),
+        _ => return Err(OffError::BadHeader { line: lines.line_no, token: keyword })
+    };
+
+    if tokens.is_empty() {
+        tokens = lines.expect_tokens()?;
+    }
+
+    if tokens.len() < 2 {
+        return Err(OffError::UnexpectedEof { line: lines.line_no });
+    }
+
+    let num_vertices : usize = lines.parse(&tokens[0])?;
+    let num_faces : usize = lines.parse(&tokens[1])?;
+
+    for _ in 0..num_vertices {
+        let tokens = lines.expect_tokens()?;
+
+        let needed = 3 + if has_normals { 3 } else { 0 } + if has_colors { 3 } else { 0 };
+        if tokens.len() < needed {
+            return Err(OffError::UnexpectedEof { line: lines.line_no });
+        }
+
+        let xyz = |offset: usize| -> Result<[f32; 3], OffError> {
+            Ok([lines.parse(&tokens[offset])?, lines.parse(&tokens[offset + 1])?, lines.parse(&tokens[offset + 2])?])
+        };
+
+        data.positions.push(xyz(0)?);
+
+        if has_normals {
+            data.normals.push(xyz(3)?);
+        }
+
+        if has_colors {
+            let start = if has_normals { 6 } else { 3 };
+            data.vertex_colors.push(lines.parse_color(&tokens[start..])?);
+        }
+    }
+
+    for _ in 0..num_faces {
+        let tokens = lines.expect_tokens()?;
+
+        // The count comes from the file, so it's compared without adding to it (which could overflow).
+        let n : usize = lines.parse(&tokens[0])?;
+        if tokens.len() - 1 < n {
+            return Err(OffError::UnexpectedEof { line: lines.line_no });
+        }
+
+        let face = tokens[1..1 + n].iter().map(|token| {
+            let index : usize = lines.parse(token)?;
+            if index < data.positions.len() {
+                Ok(index)
+            } else {
+                Err(OffError::BadIndex { line: lines.line_no, index })
+            }
+        }).collect::<Result<Vec<usize>, _>>()?;
+
+        data.faces.push(face);
+
+        // A single extra value would be a colour map index, which we don't support.
+        let color = &tokens[1 + n..];
+        let color = if color.len() >= 3 { Some(lines.parse_color(color)?) } else { None };
+
+        // The colours are only kept once any face has one, so earlier faces are back-filled.
+        if color.is_some() || !data.face_colors.is_empty() {
+            data.face_colors.resize(data.faces.len() - 1, None);
+            data.face_colors.push(color);
+        }
+    }
+
+    Ok(data)
+}
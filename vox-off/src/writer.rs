@@ -0,0 +1,57 @@
+use std::io::{BufWriter, Write};
+
+use crate::error::OffError;
+use crate::fwd::OffData;
+
+fn write_color<W: Write>(writer: &mut W, rgba: &[u8; 4]) -> std::io::Result<()> {
+    write!(writer, " {} {} {} {}", rgba[0], rgba[1], rgba[2], rgba[3])
+}
+
+/// A simple writer for OFF files.
+///
+/// The header keyword follows from the data: normals and vertex colours are written (as NOFF,
+/// COFF or CNOFF) if there are any, and face colours are appended to the faces that have one.
+/// Normals and vertex colours must then cover every vertex, which is checked before anything is
+/// written.
+///
+pub fn write_off<W: Write>(writer: &mut W, data: &OffData) -> Result<(), OffError> {
+    let has_normals = !data.normals.is_empty();
+    let has_colors = !data.vertex_colors.is_empty();
+
+    for (field, len) in [("normals", data.normals.len()), ("vertex_colors", data.vertex_colors.len())] {
+        if len != 0 && len != data.positions.len() {
+            return Err(OffError::BadLength { field, len, expected: data.positions.len() });
+        }
+    }
+
+    let mut writer = BufWriter::new(writer);
+
+    writeln!(writer, "{}{}OFF", if has_colors { "C" } else { "" }, if has_normals { "N" } else { "" })?;
+    writeln!(writer, "{} {} 0", data.positions.len(), data.faces.len())?;
+
+    for (i, p) in data.positions.iter().enumerate() {
+        write!(writer, "{} {} {}", p[0], p[1], p[2])?;
+        if has_normals {
+            let n = data.normals[i];
+            write!(writer, " {} {} {}", n[0], n[1], n[2])?;
+        }
+        if has_colors {
+            write_color(&mut writer, &data.vertex_colors[i])?;
+        }
+        writeln!(writer)?;
+    }
+
+    for (i, face) in data.faces.iter().enumerate() {
+        write!(writer, "{}", face.len())?;
+        for v in face {
+            write!(writer, " {}", v)?;
+        }
+        if let Some(Some(color)) = data.face_colors.get(i) {
+            write_color(&mut writer, color)?;
+        }
+        writeln!(writer)?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use std::path::Path;
+
+use vox_off::off;
+use vox_off::off::OffError;
+
+#[test]
+fn parse_off() {
+    let data = off::parse_from_file(Path::new("../resources/models/cube.off")).unwrap();
+
+    assert_eq!(data.positions.len(), 8);
+    assert_eq!(data.positions[6], [1.0, 1.0, 1.0]);
+    assert!(data.normals.is_empty());
+    assert_eq!(data.vertex_colors[3], [255, 255, 0, 255]);
+
+    assert_eq!(data.faces.len(), 6);
+    assert_eq!(data.faces[2], vec![0, 1, 5, 4]);
+
+    // Float colours are scaled up, and uncoloured faces are None.
+    assert_eq!(data.face_colors.len(), 6);
+    assert_eq!(data.face_colors[0], Some([255, 0, 0, 255]));
+    assert_eq!(data.face_colors[1], None);
+    assert_eq!(data.face_colors[3], Some([0, 0, 255, 128]));
+}
+
+#[test]
+fn write_off_round_trip() {
+    let data = off::parse_from_file(Path::new("../resources/models/cube.off")).unwrap();
+
+    let mut bytes = vec![];
+    off::write(&mut bytes, &data).unwrap();
+    assert_eq!(off::parse_from_bytes(&bytes).unwrap(), data);
+
+    // [Scenario] normals, awkward floats and no colours at all
+    let mut plain = data.clone();
+    plain.vertex_colors.clear();
+    plain.face_colors.clear();
+    plain.normals = vec![[0.1, -1e-7, 3.4028235e38]; 8];
+
+    let mut bytes = vec![];
+    off::write(&mut bytes, &plain).unwrap();
+    assert!(bytes.starts_with(b"NOFF\n"));
+    assert_eq!(off::parse_from_bytes(&bytes).unwrap(), plain);
+
+    // [Scenario] normals or colours for only some of the vertices
+    let mut short = plain.clone();
+    short.normals.truncate(5);
+    assert!(matches!(off::write(&mut vec![], &short), Err(OffError::BadLength { field: "normals", len: 5, expected: 8 })));
+
+    let mut short = data.clone();
+    short.vertex_colors.pop();
+    assert!(matches!(off::write(&mut vec![], &short), Err(OffError::BadLength { field: "vertex_colors", len: 7, expected: 8 })));
+}
+
+#[test]
+fn parse_malformed_off() {
+    // [Scenario] not an OFF file at all
+    let result = off::parse_from_bytes(b"ply\nformat ascii 1.0\n");
+    assert!(matches!(result, Err(OffError::BadHeader { line: 1, .. })));
+
+    // [Scenario] the counts share the keyword's line
+    let data = off::parse_from_bytes(b"OFF 3 1 0\n0 0 0\n1 0 0\n0 1 0\n3 0 1 2\n").unwrap();
+    assert_eq!(data.faces, vec![vec![0, 1, 2]]);
+
+    // [Scenario] a coordinate isn't a number
+    let result = off::parse_from_bytes(b"OFF\n1 0 0\n0 zero 0\n");
+    assert!(matches!(result, Err(OffError::BadNumber { line: 3, .. })));
+
+    // [Scenario] a face refers to a vertex that doesn't exist
+    let result = off::parse_from_bytes(b"OFF\n3 1 0\n0 0 0\n1 0 0\n0 1 0\n3 0 1 3\n");
+    assert!(matches!(result, Err(OffError::BadIndex { line: 6, index: 3 })));
+
+    // [Scenario] the file ends early
+    let result = off::parse_from_bytes(b"OFF\n3 1 0\n0 0 0\n1 0 0\n");
+    assert!(matches!(result, Err(OffError::UnexpectedEof { .. })));
+
+    // [Scenario] a face claims far more vertices than its line holds
+    let result = off::parse_from_bytes(b"OFF\n3 1 0\n0 0 0\n1 0 0\n0 1 0\n18446744073709551615 0 1 2\n");
+    assert!(matches!(result, Err(OffError::UnexpectedEof { line: 6 })));
+}
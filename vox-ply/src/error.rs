@@ -0,0 +1,61 @@
+use std::fmt;
+use std::io;
+
+/// The ways in which reading a PLY file can fail.
+///
+/// Line numbers are 1-based so they can be reported to users as-is.
+///
+#[derive(Debug)]
+pub enum PlyError {
+    /// The underlying reader failed (this includes a body that ends early).
+    Io(io::Error),
+
+    /// The header is malformed.
+    BadHeader { line: usize, message: String },
+
+    /// A value in an ascii body could not be parsed as a number.
+    BadNumber { element: String, row: usize, token: String },
+
+    /// An element needed to build a mesh lacks a property (i.e. a 'vertex' without 'x').
+    MissingProperty { element: String, property: String },
+
+    /// A face refers to a vertex that doesn't exist.
+    BadIndex { face: usize, index: f64 }
+}
+
+impl fmt::Display for PlyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlyError::Io(why) => {
+                write!(f, "i/o error: {}", why)
+            }
+            PlyError::BadHeader { line, message } => {
+                write!(f, "[{}] bad header: {}", line, message)
+            }
+            PlyError::BadNumber { element, row, token } => {
+                write!(f, "[{} {}] expected a number, got '{}'", element, row, token)
+            }
+            PlyError::MissingProperty { element, property } => {
+                write!(f, "element '{}' has no property '{}'", element, property)
+            }
+            PlyError::BadIndex { face, index } => {
+                write!(f, "face {} refers to vertex {}, which doesn't exist", face, index)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PlyError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PlyError::Io(why) => Some(why),
+            _ => None
+        }
+    }
+}
+
+impl From<io::Error> for PlyError {
+    fn from(why: io::Error) -> Self {
+        PlyError::Io(why)
+    }
+}
@@ -0,0 +1,134 @@
+/// The encodings of the body of a PLY file.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Format {
+    Ascii,
+    BinaryLittleEndian,
+    BinaryBigEndian
+}
+
+/// The scalar types that properties can have.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ScalarType {
+    Char,
+    UChar,
+    Short,
+    UShort,
+    Int,
+    UInt,
+    Float,
+    Double
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PropertyType {
+    Scalar(ScalarType),
+    List { count: ScalarType, item: ScalarType }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PropertyDef {
+    pub name: String,
+    pub ty: PropertyType
+}
+
+/// The value of a single property of a single element.
+///
+/// Every PLY scalar type fits in an f64 without loss, so values are stored that way and converted
+/// back to the declared type when written.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Scalar(f64),
+    List(Vec<f64>)
+}
+
+/// All the instances of one element (i.e. 'vertex' or 'face'), one row per instance with one value
+/// per property.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Element {
+    pub name: String,
+    pub properties: Vec<PropertyDef>,
+    pub rows: Vec<Vec<Value>>
+}
+
+/// The contents of a PLY file.
+///
+/// see: https://paulbourke.net/dataformats/ply/
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct Ply {
+    pub format: Format,
+    pub comments: Vec<String>,
+    pub obj_info: Vec<String>,
+    pub elements: Vec<Element>
+}
+
+impl ScalarType {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "char"   | "int8"    => Some(ScalarType::Char),
+            "uchar"  | "uint8"   => Some(ScalarType::UChar),
+            "short"  | "int16"   => Some(ScalarType::Short),
+            "ushort" | "uint16"  => Some(ScalarType::UShort),
+            "int"    | "int32"   => Some(ScalarType::Int),
+            "uint"   | "uint32"  => Some(ScalarType::UInt),
+            "float"  | "float32" => Some(ScalarType::Float),
+            "double" | "float64" => Some(ScalarType::Double),
+            _ => None
+        }
+    }
+
+    /// [returns] the number of bytes a value of this type takes up in a binary body.
+    pub fn size(&self) -> usize {
+        match self {
+            ScalarType::Char   | ScalarType::UChar  => 1,
+            ScalarType::Short  | ScalarType::UShort => 2,
+            ScalarType::Int    | ScalarType::UInt   | ScalarType::Float => 4,
+            ScalarType::Double => 8
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ScalarType::Char   => "char",
+            ScalarType::UChar  => "uchar",
+            ScalarType::Short  => "short",
+            ScalarType::UShort => "ushort",
+            ScalarType::Int    => "int",
+            ScalarType::UInt   => "uint",
+            ScalarType::Float  => "float",
+            ScalarType::Double => "double"
+        }
+    }
+}
+
+impl Element {
+    /// [returns] the index of the named property, if there is one.
+    pub fn property_index(&self, name: &str) -> Option<usize> {
+        self.properties.iter().position(|p| p.name == name)
+    }
+
+    /// [returns] the values of a scalar property for every row, if there is such a property.
+    pub fn scalars(&self, name: &str) -> Option<Vec<f64>> {
+        let i = self.property_index(name)?;
+        self.rows.iter().map(|row| match &row[i] {
+            Value::Scalar(v) => Some(*v),
+            Value::List(_) => None
+        }).collect()
+    }
+
+    /// [returns] the values of a list property for every row, if there is such a property.
+    pub fn lists(&self, name: &str) -> Option<Vec<&[f64]>> {
+        let i = self.property_index(name)?;
+        self.rows.iter().map(|row| match &row[i] {
+            Value::Scalar(_) => None,
+            Value::List(v) => Some(&v[..])
+        }).collect()
+    }
+}
+
+impl Ply {
+    pub fn element(&self, name: &str) -> Option<&Element> {
+        self.elements.iter().find(|e| e.name == name)
+    }
+}
@@ -0,0 +1,35 @@
+pub mod error;
+pub mod fwd;
+
+mod parser;
+mod writer;
+
+pub mod ply {
+    use std::fs::File;
+    use std::io::{BufReader, Read, Write};
+    use std::path::Path;
+
+    pub use crate::error::PlyError;
+
+    use crate::fwd::Ply;
+    use crate::parser;
+    use crate::writer;
+
+    pub fn parse_from_file(path: &Path) -> Result<Ply, PlyError> {
+        parse_from_reader(File::open(path)?)
+    }
+
+    pub fn parse_from_reader<R: Read>(reader: R) -> Result<Ply, PlyError> {
+        parser::ply_from_reader(BufReader::new(reader))
+    }
+
+    pub fn parse_from_bytes(bytes: &[u8]) -> Result<Ply, PlyError> {
+        parser::ply_from_reader(bytes)
+    }
+
+    /// Writes `ply` in the encoding given by `ply.format`.
+    ///
+    pub fn write<W: Write>(mut writer: W, ply: &Ply) -> Result<(), PlyError> {
+        writer::write_ply(&mut writer, ply)
+    }
+}
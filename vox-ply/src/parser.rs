@@ -0,0 +1,223 @@
+use std::io::{self, BufRead, Read};
+
+use byteorder::{BigEndian, ByteOrder, LittleEndian, ReadBytesExt};
+
+use crate::error::PlyError;
+use crate::fwd::{Element, Format, Ply, PropertyDef, PropertyType, ScalarType, Value};
+
+/// Guards against allocating ridiculous amounts of memory up-front for a corrupt element count.
+const MAX_PREALLOCATED_ROWS: usize = 1 << 20;
+
+/// Parses the header.
+///
+/// [returns] the (still empty) elements, along with the number of rows declared for each.
+fn parse_header<R: BufRead>(reader: &mut R) -> Result<(Ply, Vec<usize>), PlyError> {
+    let mut ply = Ply { format: Format::Ascii, comments: vec![], obj_info: vec![], elements: vec![] };
+    let mut counts = vec![];
+    let mut element_lines = vec![];
+    let mut format = None;
+
+    let mut line = String::new();
+    let mut line_no = 0;
+
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Err(PlyError::BadHeader { line: line_no, message: "missing 'end_header'".to_string() });
+        }
+        line_no += 1;
+
+        let bad_header = |message: &str| PlyError::BadHeader { line: line_no, message: message.to_string() };
+
+        let raw = line.trim();
+        let tokens : Vec<&str> = raw.split_whitespace().collect();
+
+        if line_no == 1 {
+            if raw != "ply" {
+                return Err(bad_header("expected 'ply'"));
+            }
+            continue;
+        }
+
+        match tokens.first().copied() {
+            Some("format") => {
+                format = match tokens[1..] {
+                    ["ascii", "1.0"] => Some(Format::Ascii),
+                    ["binary_little_endian", "1.0"] => Some(Format::BinaryLittleEndian),
+                    ["binary_big_endian", "1.0"] => Some(Format::BinaryBigEndian),
+                    _ => return Err(bad_header("unsupported format"))
+                };
+            }
+
+            Some("comment") => {
+                ply.comments.push(raw["comment".len()..].trim().to_string());
+            }
+
+            Some("obj_info") => {
+                ply.obj_info.push(raw["obj_info".len()..].trim().to_string());
+            }
+
+            Some("element") => {
+                let (name, count) = match tokens[1..] {
+                    [name, count] => (name, count.parse::<usize>().map_err(|_| bad_header("bad element count"))?),
+                    _ => return Err(bad_header("expected 'element <name> <count>'"))
+                };
+
+                ply.elements.push(Element {
+                    name: name.to_string(),
+                    properties: vec![],
+                    rows: Vec::with_capacity(count.min(MAX_PREALLOCATED_ROWS))
+                });
+                counts.push(count);
+                element_lines.push(line_no);
+            }
+
+            Some("property") => {
+                let scalar = |name: &str| ScalarType::from_name(name).ok_or_else(|| bad_header("unknown property type"));
+
+                let (ty, name) = match tokens[1..] {
+                    ["list", count, item, name] => (PropertyType::List { count: scalar(count)?, item: scalar(item)? }, name),
+                    [ty, name] => (PropertyType::Scalar(scalar(ty)?), name),
+                    _ => return Err(bad_header("expected 'property <type> <name>'"))
+                };
+
+                match ply.elements.last_mut() {
+                    Some(element) => element.properties.push(PropertyDef { name: name.to_string(), ty }),
+                    None => return Err(bad_header("property declared before any element"))
+                }
+            }
+
+            Some("end_header") => break,
+
+            _ => return Err(bad_header("unexpected keyword"))
+        }
+    }
+
+    ply.format = format.ok_or(PlyError::BadHeader { line: line_no, message: "missing 'format'".to_string() })?;
+
+    // Rows without properties take up no room in the body, so a corrupt count would never run out.
+    for ((element, &count), &line) in ply.elements.iter().zip(&counts).zip(&element_lines) {
+        if element.properties.is_empty() && count > 0 {
+            return Err(PlyError::BadHeader { line, message: format!("element '{}' has rows but no properties", element.name) });
+        }
+    }
+
+    Ok((ply, counts))
+}
+
+/// Reads the rows of every element from an ascii body.
+fn parse_ascii_body<R: Read>(reader: &mut R, ply: &mut Ply, counts: &[usize]) -> Result<(), PlyError> {
+    let mut body = String::new();
+    reader.read_to_string(&mut body)?;
+
+    let mut tokens = body.split_whitespace();
+
+    for (element, &count) in ply.elements.iter_mut().zip(counts) {
+        for row_i in 0..count {
+            let mut next = || -> Result<f64, PlyError> {
+                let token = tokens.next().ok_or_else(|| io::Error::from(io::ErrorKind::UnexpectedEof))?;
+                token.parse::<f64>().map_err(|_| PlyError::BadNumber {
+                    element: element.name.clone(),
+                    row: row_i,
+                    token: token.to_string()
+                })
+            };
+
+            let mut row = Vec::with_capacity(element.properties.len());
+            for property in &element.properties {
+                row.push(match property.ty {
+                    PropertyType::Scalar(_) => Value::Scalar(next()?),
+                    PropertyType::List { .. } => {
+                        // The length comes from the file, so nothing is allocated for it up front.
+                        let n = next()? as usize;
+                        let mut list = vec![];
+                        for _ in 0..n {
+                            list.push(next()?);
+                        }
+                        Value::List(list)
+                    }
+                });
+            }
+
+            element.rows.push(row);
+        }
+    }
+
+    Ok(())
+}
+
+fn read_scalar<B: ByteOrder, R: Read>(reader: &mut R, ty: ScalarType) -> io::Result<f64> {
+    Ok(match ty {
+        ScalarType::Char   => reader.read_i8()? as f64,
+        ScalarType::UChar  => reader.read_u8()? as f64,
+        ScalarType::Short  => reader.read_i16::<B>()? as f64,
+        ScalarType::UShort => reader.read_u16::<B>()? as f64,
+        ScalarType::Int    => reader.read_i32::<B>()? as f64,
+        ScalarType::UInt   => reader.read_u32::<B>()? as f64,
+        ScalarType::Float  => reader.read_f32::<B>()? as f64,
+        ScalarType::Double => reader.read_f64::<B>()?
+    })
+}
+
+/// Reads the rows of every element from a binary body with byte order `B`.
+///
+/// [note]    An element is refused up front if even its smallest rows (i.e. with empty lists) couldn't
+///           fit in what is left of the body.
+fn parse_binary_body<B: ByteOrder, R: Read>(reader: &mut R, ply: &mut Ply, counts: &[usize]) -> Result<(), PlyError> {
+    let mut body = vec![];
+    reader.read_to_end(&mut body)?;
+    let mut reader = io::Cursor::new(&body[..]);
+
+    for (element, &count) in ply.elements.iter_mut().zip(counts) {
+        let min_row_len : usize = element.properties.iter().map(|property| match property.ty {
+            PropertyType::Scalar(ty) => ty.size(),
+            PropertyType::List { count, .. } => count.size()
+        }).sum();
+
+        let remaining = body.len() - reader.position() as usize;
+        if count.checked_mul(min_row_len).is_none_or(|len| len > remaining) {
+            let message = format!("element '{}' declares more rows than the rest of the body holds", element.name);
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, message).into());
+        }
+
+        for _ in 0..count {
+            let mut row = Vec::with_capacity(element.properties.len());
+            for property in &element.properties {
+                row.push(match property.ty {
+                    PropertyType::Scalar(ty) => Value::Scalar(read_scalar::<B, _>(&mut reader, ty)?),
+                    PropertyType::List { count, item } => {
+                        // As for ascii bodies, the length isn't trusted with an allocation.
+                        let n = read_scalar::<B, _>(&mut reader, count)? as usize;
+                        let mut list = vec![];
+                        for _ in 0..n {
+                            list.push(read_scalar::<B, _>(&mut reader, item)?);
+                        }
+                        Value::List(list)
+                    }
+                });
+            }
+
+            element.rows.push(row);
+        }
+    }
+
+    Ok(())
+}
+
+/// A simple parser for PLY files, in any of the three encodings.
+///
+/// Every element and property is kept, not just the ones that make up a mesh.
+///
+/// see: https://paulbourke.net/dataformats/ply/
+///
+pub fn ply_from_reader<R: BufRead>(mut reader: R) -> Result<Ply, PlyError> {
+    let (mut ply, counts) = parse_header(&mut reader)?;
+
+    match ply.format {
+        Format::Ascii              => parse_ascii_body(&mut reader, &mut ply, &counts)?,
+        Format::BinaryLittleEndian => parse_binary_body::<LittleEndian, R>(&mut reader, &mut ply, &counts)?,
+        Format::BinaryBigEndian    => parse_binary_body::<BigEndian, R>(&mut reader, &mut ply, &counts)?
+    }
+
+    Ok(ply)
+}
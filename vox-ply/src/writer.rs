@@ -0,0 +1,129 @@
+use std::io::{self, BufWriter, Write};
+
+use byteorder::{BigEndian, ByteOrder, LittleEndian, WriteBytesExt};
+
+use crate::error::PlyError;
+use crate::fwd::{Format, Ply, PropertyType, ScalarType, Value};
+
+fn write_header<W: Write>(writer: &mut W, ply: &Ply) -> io::Result<()> {
+    writeln!(writer, "ply")?;
+    writeln!(writer, "format {} 1.0", match ply.format {
+        Format::Ascii => "ascii",
+        Format::BinaryLittleEndian => "binary_little_endian",
+        Format::BinaryBigEndian => "binary_big_endian"
+    })?;
+
+    for comment in &ply.comments {
+        writeln!(writer, "comment {}", comment)?;
+    }
+    for info in &ply.obj_info {
+        writeln!(writer, "obj_info {}", info)?;
+    }
+
+    for element in &ply.elements {
+        writeln!(writer, "element {} {}", element.name, element.rows.len())?;
+        for property in &element.properties {
+            match property.ty {
+                PropertyType::Scalar(ty) => {
+                    writeln!(writer, "property {} {}", ty.name(), property.name)?
+                }
+                PropertyType::List { count, item } => {
+                    writeln!(writer, "property list {} {} {}", count.name(), item.name(), property.name)?
+                }
+            }
+        }
+    }
+
+    writeln!(writer, "end_header")
+}
+
+/// [returns] `v` formatted as the declared type, in the shortest form that reads back exactly.
+fn format_scalar(ty: ScalarType, v: f64) -> String {
+    match ty {
+        ScalarType::Float  => format!("{}", v as f32),
+        ScalarType::Double => format!("{}", v),
+        _                  => format!("{}", v as i64)
+    }
+}
+
+fn write_ascii_body<W: Write>(writer: &mut W, ply: &Ply) -> io::Result<()> {
+    for element in &ply.elements {
+        for row in &element.rows {
+            let mut fields = vec![];
+            for (property, value) in element.properties.iter().zip(row) {
+                match (property.ty, value) {
+                    (PropertyType::Scalar(ty), Value::Scalar(v)) => fields.push(format_scalar(ty, *v)),
+                    (PropertyType::List { count, item }, Value::List(vs)) => {
+                        fields.push(format_scalar(count, vs.len() as f64));
+                        fields.extend(vs.iter().map(|v| format_scalar(item, *v)));
+                    }
+                    _ => return Err(mismatched(&element.name, &property.name))
+                }
+            }
+            writeln!(writer, "{}", fields.join(" "))?;
+        }
+    }
+
+    Ok(())
+}
+
+fn write_scalar<B: ByteOrder, W: Write>(writer: &mut W, ty: ScalarType, v: f64) -> io::Result<()> {
+    match ty {
+        ScalarType::Char   => writer.write_i8(v as i8),
+        ScalarType::UChar  => writer.write_u8(v as u8),
+        ScalarType::Short  => writer.write_i16::<B>(v as i16),
+        ScalarType::UShort => writer.write_u16::<B>(v as u16),
+        ScalarType::Int    => writer.write_i32::<B>(v as i32),
+        ScalarType::UInt   => writer.write_u32::<B>(v as u32),
+        ScalarType::Float  => writer.write_f32::<B>(v as f32),
+        ScalarType::Double => writer.write_f64::<B>(v)
+    }
+}
+
+fn write_binary_body<B: ByteOrder, W: Write>(writer: &mut W, ply: &Ply) -> io::Result<()> {
+    for element in &ply.elements {
+        for row in &element.rows {
+            for (property, value) in element.properties.iter().zip(row) {
+                match (property.ty, value) {
+                    (PropertyType::Scalar(ty), Value::Scalar(v)) => write_scalar::<B, W>(writer, ty, *v)?,
+                    (PropertyType::List { count, item }, Value::List(vs)) => {
+                        write_scalar::<B, W>(writer, count, vs.len() as f64)?;
+                        for v in vs {
+                            write_scalar::<B, W>(writer, item, *v)?;
+                        }
+                    }
+                    _ => return Err(mismatched(&element.name, &property.name))
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn mismatched(element: &str, property: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!("the value of '{}.{}' doesn't match its declared type", element, property)
+    )
+}
+
+/// A simple writer for PLY files, in the encoding given by `ply.format`.
+///
+/// Values are converted to their declared types, so anything read by the parser is written back
+/// unchanged.
+///
+pub fn write_ply<W: Write>(writer: &mut W, ply: &Ply) -> Result<(), PlyError> {
+    let mut writer = BufWriter::new(writer);
+
+    write_header(&mut writer, ply)?;
+
+    match ply.format {
+        Format::Ascii              => write_ascii_body(&mut writer, ply)?,
+        Format::BinaryLittleEndian => write_binary_body::<LittleEndian, _>(&mut writer, ply)?,
+        Format::BinaryBigEndian    => write_binary_body::<BigEndian, _>(&mut writer, ply)?
+    }
+
+    writer.flush()?;
+    Ok(())
+}
@@ -0,0 +1,100 @@
+use std::path::Path;
+
+use vox_ply::fwd::{Format, PropertyType, ScalarType, Value};
+use vox_ply::ply;
+use vox_ply::ply::PlyError;
+
+#[test]
+fn parse_ply() {
+    let data = ply::parse_from_file(Path::new("../resources/models/cube-colors.ply")).unwrap();
+
+    assert_eq!(data.format, Format::Ascii);
+    assert_eq!(data.comments.len(), 1);
+    assert_eq!(data.obj_info, vec!["scanned with nothing in particular".to_string()]);
+    assert_eq!(data.elements.len(), 3);
+
+    let vertex = data.element("vertex").unwrap();
+    assert_eq!(vertex.rows.len(), 8);
+    assert_eq!(vertex.scalars("red").unwrap()[3], 255.0);
+    assert_eq!(vertex.scalars("confidence").unwrap()[6], 0.125);
+
+    let face = data.element("face").unwrap();
+    assert_eq!(face.properties[0].ty, PropertyType::List { count: ScalarType::UChar, item: ScalarType::Int });
+    assert_eq!(face.lists("vertex_indices").unwrap()[2], &[0.0, 1.0, 5.0, 4.0][..]);
+
+    // Elements that have nothing to do with meshes are kept too.
+    assert_eq!(data.element("edge").unwrap().rows, vec![vec![Value::Scalar(0.0), Value::Scalar(6.0)]]);
+}
+
+#[test]
+fn write_ply_round_trip() {
+    let data = ply::parse_from_file(Path::new("../resources/models/cube-colors.ply")).unwrap();
+
+    for format in [Format::Ascii, Format::BinaryLittleEndian, Format::BinaryBigEndian] {
+        let mut converted = data.clone();
+        converted.format = format;
+
+        let mut bytes = vec![];
+        ply::write(&mut bytes, &converted).unwrap();
+
+        assert_eq!(ply::parse_from_bytes(&bytes).unwrap(), converted, "{:?}", format);
+    }
+}
+
+#[test]
+fn parse_binary_ply_endianness() {
+    let header = |format: &str| format!(
+        "ply\nformat {} 1.0\nelement vertex 1\nproperty short x\nproperty double y\nproperty uint z\nend_header\n",
+        format
+    );
+
+    let mut little = header("binary_little_endian").into_bytes();
+    little.extend((-2i16).to_le_bytes());
+    little.extend(1.5f64.to_le_bytes());
+    little.extend(70000u32.to_le_bytes());
+
+    let mut big = header("binary_big_endian").into_bytes();
+    big.extend((-2i16).to_be_bytes());
+    big.extend(1.5f64.to_be_bytes());
+    big.extend(70000u32.to_be_bytes());
+
+    for bytes in [little, big] {
+        let data = ply::parse_from_bytes(&bytes).unwrap();
+        assert_eq!(data.elements[0].rows[0], vec![Value::Scalar(-2.0), Value::Scalar(1.5), Value::Scalar(70000.0)]);
+    }
+}
+
+#[test]
+fn parse_malformed_ply() {
+    // [Scenario] not a ply file at all
+    let result = ply::parse_from_bytes(b"solid cube\n");
+    assert!(matches!(result, Err(PlyError::BadHeader { line: 1, .. })));
+
+    // [Scenario] unknown property type
+    let result = ply::parse_from_bytes(b"ply\nformat ascii 1.0\nelement vertex 1\nproperty quad x\nend_header\n1\n");
+    assert!(matches!(result, Err(PlyError::BadHeader { line: 4, .. })));
+
+    // [Scenario] the header never ends
+    let result = ply::parse_from_bytes(b"ply\nformat ascii 1.0\nelement vertex 1\n");
+    assert!(matches!(result, Err(PlyError::BadHeader { .. })));
+
+    // [Scenario] a value isn't a number
+    let result = ply::parse_from_bytes(b"ply\nformat ascii 1.0\nelement vertex 2\nproperty float x\nend_header\n1\nfoo\n");
+    assert!(matches!(result, Err(PlyError::BadNumber { row: 1, .. })));
+
+    // [Scenario] the body ends early
+    let result = ply::parse_from_bytes(b"ply\nformat binary_little_endian 1.0\nelement vertex 2\nproperty float x\nend_header\n\0\0\0\0");
+    assert!(matches!(result, Err(PlyError::Io(_))));
+
+    // [Scenario] an element with no properties claims to have rows (which would take no input to read)
+    let result = ply::parse_from_bytes(b"ply\nformat ascii 1.0\nelement junk 18446744073709551615\nend_header\n");
+    assert!(matches!(result, Err(PlyError::BadHeader { line: 3, .. })));
+
+    // [Scenario] a binary element declares more rows than the rest of the file could hold
+    let result = ply::parse_from_bytes(b"ply\nformat binary_little_endian 1.0\nelement vertex 18446744073709551615\nproperty float x\nend_header\n\0\0\0\0");
+    assert!(matches!(result, Err(PlyError::Io(ref why)) if why.kind() == std::io::ErrorKind::UnexpectedEof));
+
+    // [Scenario] a list claims to be enormous
+    let result = ply::parse_from_bytes(b"ply\nformat ascii 1.0\nelement face 1\nproperty list uchar int v\nend_header\n1e30 0 1 2\n");
+    assert!(matches!(result, Err(PlyError::Io(_))));
+}
@@ -0,0 +1,67 @@
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use vox_stl::fwd::{Facet, Pt3};
+use vox_stl::stl;
+
+/// The number of triangles in the generated model, override with VOX_BENCH_TRIANGLES.
+const DEFAULT_TRIANGLES: usize = 2_000_000;
+
+/// The number of timed runs of each path, the fastest of which is reported.
+const RUNS: usize = 5;
+
+/// Generates a (meaningless, but realistic in size) triangle soup.
+fn generate_facets(n: usize) -> Vec<Facet> {
+    (0..n).map(|i| {
+        let x = i as f32 * 0.001;
+        Facet {
+            tri: [
+                Pt3([x, 0.0, 1.0]),
+                Pt3([x + 0.5, 1.0, 0.0]),
+                Pt3([x, 1.0, x.sin()])
+            ],
+            normal: Pt3([0.0, 0.0, 1.0]),
+            attribute: i as u16
+        }
+    }).collect()
+}
+
+fn fastest_of<T>(f: impl Fn() -> T) -> (Duration, T) {
+    let mut best = Duration::MAX;
+    let mut result = None;
+
+    for _ in 0..RUNS {
+        let start = Instant::now();
+        let r = f();
+        best = best.min(start.elapsed());
+        result = Some(r);
+    }
+
+    (best, result.unwrap())
+}
+
+fn main() {
+    let n = std::env::var("VOX_BENCH_TRIANGLES").ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(DEFAULT_TRIANGLES);
+
+    let path = std::env::temp_dir().join(format!("vox-stl-bench-{}.stl", std::process::id()));
+    stl::write_binary(std::fs::File::create(&path).unwrap(), &generate_facets(n), None).unwrap();
+
+    let (streamed_t, streamed) = fastest_of(|| {
+        stl::FacetReader::open(Path::new(&path)).unwrap().collect::<Result<Vec<_>, _>>().unwrap()
+    });
+
+    let (parallel_t, parallel) = fastest_of(|| {
+        stl::parse_from_file(Path::new(&path)).unwrap()
+    });
+
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(streamed, parallel);
+
+    println!("binary STL with {} triangles ({} MB)", n, (84 + 50 * n) / 1_000_000);
+    println!("  streaming reader        {:>10.2?}", streamed_t);
+    println!("  mmap + parallel decode  {:>10.2?}", parallel_t);
+    println!("  speedup                 {:>10.1}x", streamed_t.as_secs_f64() / parallel_t.as_secs_f64());
+}
@@ -0,0 +1,78 @@
+/// The two common (unofficial) ways of packing an RGB555 colour into a facet's attribute word.
+///
+/// see: https://en.wikipedia.org/wiki/STL_(file_format)#Colour_in_binary_STL
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ColorConvention {
+    /// VisCAM and SolidView: blue in bits 0-4, green in 5-9, red in 10-14, and bit 15 set if the
+    /// colour is valid.
+    VisCam,
+
+    /// Materialise Magics: red in bits 0-4, green in 5-9, blue in 10-14, and bit 15 *clear* if the
+    /// facet has its own colour (otherwise the object colour from the header applies).
+    Magics
+}
+
+/// The colours that Materialise Magics stores in the 80-byte header.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct HeaderColors {
+    /// The object colour (RGBA), from "COLOR=".
+    pub color: Option<[u8; 4]>,
+
+    /// The diffuse, specular and ambient colours (RGBA), from "MATERIAL=".
+    pub material: Option<[[u8; 4]; 3]>
+}
+
+fn expand_5bit(v: u16) -> u8 {
+    let v = (v & 0x1f) as u8;
+    (v << 3) | (v >> 2)
+}
+
+impl ColorConvention {
+    /// [returns] Magics if the header carries Magics colour information, else VisCam.
+    pub fn detect(header: &[u8; 80]) -> Self {
+        let colors = HeaderColors::from_header(header);
+        if colors.color.is_some() || colors.material.is_some() {
+            ColorConvention::Magics
+        } else {
+            ColorConvention::VisCam
+        }
+    }
+
+    /// [returns] the RGB colour stored in `attribute`, or None if the facet doesn't have one.
+    pub fn decode(&self, attribute: u16) -> Option<[u8; 3]> {
+        let (lo, mid, hi) = (expand_5bit(attribute), expand_5bit(attribute >> 5), expand_5bit(attribute >> 10));
+        let flag = attribute & 0x8000 != 0;
+
+        match self {
+            ColorConvention::VisCam => if flag { Some([hi, mid, lo]) } else { None },
+            ColorConvention::Magics => if flag { None } else { Some([lo, mid, hi]) }
+        }
+    }
+
+    /// [returns] the attribute word that stores `rgb`, truncated to 5 bits per channel.
+    pub fn encode(&self, rgb: [u8; 3]) -> u16 {
+        let [r, g, b] = rgb.map(|c| (c >> 3) as u16);
+
+        match self {
+            ColorConvention::VisCam => 0x8000 | (r << 10) | (g << 5) | b,
+            ColorConvention::Magics => (b << 10) | (g << 5) | r
+        }
+    }
+}
+
+impl HeaderColors {
+    pub fn from_header(header: &[u8; 80]) -> Self {
+        let find = |key: &[u8], len: usize| -> Option<&[u8]> {
+            let start = header.windows(key.len()).position(|w| w == key)? + key.len();
+            header.get(start..start + len)
+        };
+
+        let rgba = |bytes: &[u8]| [bytes[0], bytes[1], bytes[2], bytes[3]];
+
+        HeaderColors {
+            color: find(b"COLOR=", 4).map(rgba),
+            material: find(b"MATERIAL=", 12).map(|m| [rgba(&m[0..4]), rgba(&m[4..8]), rgba(&m[8..12])])
+        }
+    }
+}
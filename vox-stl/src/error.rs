@@ -0,0 +1,67 @@
+use std::fmt;
+use std::io;
+
+/// The ways in which reading an STL file can fail.
+///
+/// Line and column numbers are 1-based so they can be reported to users as-is.
+///
+#[derive(Debug)]
+pub enum StlError {
+    /// The underlying reader failed (this includes invalid UTF-8 in an ascii file).
+    Io(io::Error),
+
+    /// The binary body ends before the number of triangles declared in the header.
+    TruncatedBinary { expected: u32, available: u32 },
+
+    /// The binary body holds more whole triangles than the header declares.
+    TriangleCountMismatch { header: u32, body: u64 },
+
+    /// A token that should have been a number could not be parsed as one.
+    BadNumber { line: usize, column: usize, token: String },
+
+    /// A line did not start with any of the expected keywords.
+    UnexpectedToken { line: usize, token: String, expected: &'static [&'static str] },
+
+    /// The ascii file ended while the parser still expected more input.
+    UnexpectedEof { line: usize, expected: &'static [&'static str] },
+}
+
+impl fmt::Display for StlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StlError::Io(why) => {
+                write!(f, "i/o error: {}", why)
+            }
+            StlError::TruncatedBinary { expected, available } => {
+                write!(f, "binary body is truncated: header declares {} triangles, body holds {}", expected, available)
+            }
+            StlError::TriangleCountMismatch { header, body } => {
+                write!(f, "header declares {} triangles but the body is {} bytes long", header, body)
+            }
+            StlError::BadNumber { line, column, token } => {
+                write!(f, "[{}:{}] expected a number, got '{}'", line, column, token)
+            }
+            StlError::UnexpectedToken { line, token, expected } => {
+                write!(f, "[{}] unexpected token '{}', expected one of: {}", line, token, expected.join(", "))
+            }
+            StlError::UnexpectedEof { line, expected } => {
+                write!(f, "[{}] unexpected end of file, expected one of: {}", line, expected.join(", "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for StlError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            StlError::Io(why) => Some(why),
+            _ => None
+        }
+    }
+}
+
+impl From<io::Error> for StlError {
+    fn from(why: io::Error) -> Self {
+        StlError::Io(why)
+    }
+}
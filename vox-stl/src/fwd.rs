@@ -1,11 +1,22 @@
-#[derive(Default, Copy, Clone)]
+#[derive(Default, Copy, Clone, Debug, PartialEq)]
 pub struct Pt3(pub [f32; 3]);
 
 pub type Normal = Pt3;
 pub type Vertex = Pt3;
 
-#[derive(Default, Copy, Clone)]
+#[derive(Default, Copy, Clone, Debug, PartialEq)]
 pub struct Facet {
     pub tri: [Vertex; 3],
-    pub normal: Normal
+    pub normal: Normal,
+
+    // The 'attribute byte count' of binary files (always 0 for ascii files), which some exporters
+    // use to store a colour. See `crate::color`.
+    pub attribute: u16
+}
+
+/// A named group of facets. Ascii files may hold several, binary files always hold one (unnamed).
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct Solid {
+    pub name: String,
+    pub facets: Vec<Facet>
 }
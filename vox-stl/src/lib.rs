@@ -1,55 +1,207 @@
-
+pub mod color;
+pub mod error;
 pub mod fwd;
 
 mod parser_binary;
 mod parser_ascii;
+mod reader;
+mod writer_binary;
+mod writer_ascii;
 
 pub mod stl {
     use std::fs::File;
-    use std::io::Read;
+    use std::io::{Cursor, Read, Seek, SeekFrom, Write};
     use std::path::Path;
 
-    use crate::fwd::Facet;
-    use crate::parser_ascii;
+    pub use crate::error::StlError;
+    pub use crate::reader::FacetReader;
+
+    use crate::fwd::{Facet, Solid};
     use crate::parser_binary;
+    use crate::parser_binary::{FACET_LEN, HEADER_LEN};
+    use crate::writer_ascii;
+    use crate::writer_binary;
+
+    /// The header written to binary files when the caller doesn't supply one.
+    ///
+    /// [note] This mustn't begin with 'solid', or older readers will mistake the file for ascii.
+    const DEFAULT_BINARY_HEADER: &[u8] = b"binary STL written by vox-stl";
 
-    enum Encoding {
+    /// The number of leading bytes inspected when looking for non-text content.
+    const SNIFF_LEN: usize = 512;
+
+    #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+    pub enum Encoding {
         Ascii,
         Binary
     }
 
-    pub fn parse_from_file(path: &Path) -> Result<Vec<Facet>, String> {
-        let mut file = File::open(&path).unwrap();
+    /// Binary files are memory-mapped and decoded in parallel.
+    ///
+    pub fn parse_from_file(path: &Path) -> Result<Vec<Facet>, StlError> {
+        let file = File::open(path)?;
 
-        match get_stl_encoding(&mut file)? {
-            Encoding::Ascii =>  { parser_ascii::facets_from_ascii_stl(&mut file) }
-            Encoding::Binary => { parser_binary::facets_from_binary_stl(&mut file) }
+        if file.metadata()?.len() == 0 {
+            // Empty files can't be mapped, but this still gives a proper error.
+            return parse_from_reader(file);
         }
+
+        // [safety] The mapping is only valid as long as nobody truncates or rewrites the file
+        //          while we're parsing it. This is the same contract as any other mmap-based reader.
+        let mapped = unsafe { memmap2::Mmap::map(&file)? };
+        parse_from_bytes(&mapped)
+    }
+
+    /// Like `parse_from_file`, but skips detection and parses the file as `encoding`.
+    ///
+    pub fn parse_from_file_with_encoding(path: &Path, encoding: Encoding) -> Result<Vec<Facet>, StlError> {
+        parse_from_reader_with_encoding(File::open(path)?, encoding)
+    }
+
+    /// Parses the STL data that starts at the current position of `reader`.
+    ///
+    pub fn parse_from_reader<R: Read + Seek>(mut reader: R) -> Result<Vec<Facet>, StlError> {
+        let encoding = detect_encoding(&mut reader)?;
+        parse_from_reader_with_encoding(reader, encoding)
+    }
+
+    /// Like `parse_from_reader`, but skips detection and parses the data as `encoding`.
+    ///
+    pub fn parse_from_reader_with_encoding<R: Read + Seek>(reader: R, encoding: Encoding) -> Result<Vec<Facet>, StlError> {
+        FacetReader::with_encoding(reader, encoding)?.collect()
     }
 
-    /// Returns StlEncoding::Ascii if the file begins with 'solid', else StlEncoding::Binary
+    /// Parses STL data that is already in memory (i.e. a network response or an archive entry).
     ///
-    fn get_stl_encoding(file: &mut File) -> Result<Encoding, String> {
-        let parsed_header = {
-            let mut bytes = [0; 5];
-            file.read_exact(&mut bytes).expect("could not read 5 bytes from file header");
-            String::from_utf8(Vec::from(bytes))
+    /// Binary data is decoded in parallel.
+    ///
+    pub fn parse_from_bytes(bytes: &[u8]) -> Result<Vec<Facet>, StlError> {
+        let mut cursor = Cursor::new(bytes);
+
+        match detect_encoding(&mut cursor)? {
+            Encoding::Ascii => parse_from_reader_with_encoding(cursor, Encoding::Ascii),
+            Encoding::Binary => parser_binary::facets_from_binary_bytes(bytes)
+        }
+    }
+
+    /// Like `parse_from_file`, but keeps the facets grouped by the solid they belong to.
+    ///
+    pub fn parse_solids_from_file(path: &Path) -> Result<Vec<Solid>, StlError> {
+        parse_solids_from_reader(File::open(path)?)
+    }
+
+    /// Like `parse_from_reader`, but keeps the facets grouped by the solid they belong to.
+    ///
+    pub fn parse_solids_from_reader<R: Read + Seek>(reader: R) -> Result<Vec<Solid>, StlError> {
+        let mut reader = FacetReader::new(reader)?;
+        let mut solids : Vec<Solid> = vec![];
+
+        // Solids are created as their names show up, so that empty ones are kept too.
+        let sync = |solids: &mut Vec<Solid>, names: &[String]| {
+            for name in &names[solids.len()..] {
+                solids.push(Solid { name: name.clone(), facets: vec![] });
+            }
         };
 
-        match parsed_header {
-            Err(msg) => {  Err(msg.to_string()) }
+        while let Some(facet) = reader.next() {
+            let facet = facet?;
+            sync(&mut solids, reader.solid_names());
+            solids[reader.solid_index()].facets.push(facet);
+        }
 
-            Ok(header) => {
-                let encoding = {
-                    if header == "solid" {
-                        Encoding::Ascii
-                    } else {
-                        Encoding::Binary
-                    }
-                };
+        sync(&mut solids, reader.solid_names());
 
-                Ok(encoding)
+        Ok(solids)
+    }
+
+    /// Like `parse_from_bytes`, but keeps the facets grouped by the solid they belong to.
+    ///
+    pub fn parse_solids_from_bytes(bytes: &[u8]) -> Result<Vec<Solid>, StlError> {
+        parse_solids_from_reader(Cursor::new(bytes))
+    }
+
+    /// Returns Encoding::Ascii if the data looks like an ascii STL, else Encoding::Binary
+    ///
+    /// Beginning with 'solid' isn't enough on its own, since many exporters write binary files
+    /// whose 80-byte header starts with 'solid'. So the data is only considered ascii if:
+    ///     1. it begins with 'solid',
+    ///     2. the binary triangle count doesn't agree with the length of the data, and
+    ///     3. the leading bytes are all printable (UTF-8) text.
+    ///
+    /// The reader is left where it was found.
+    ///
+    pub fn detect_encoding<R: Read + Seek>(reader: &mut R) -> Result<Encoding, StlError> {
+        let start = reader.stream_position()?;
+        let len = reader.seek(SeekFrom::End(0))? - start;
+        reader.seek(SeekFrom::Start(start))?;
+
+        let mut leading = Vec::with_capacity(SNIFF_LEN);
+        reader.by_ref().take(SNIFF_LEN as u64).read_to_end(&mut leading)?;
+        reader.seek(SeekFrom::Start(start))?;
+
+        if !leading.starts_with(b"solid") {
+            return Ok(Encoding::Binary);
+        }
+
+        if leading.len() >= HEADER_LEN as usize {
+            let num_triangles = u32::from_le_bytes([leading[80], leading[81], leading[82], leading[83]]);
+            let body_len = len - HEADER_LEN;
+            let expected_len = num_triangles as u64 * FACET_LEN;
+
+            // The binary parser tolerates fewer than one record's worth of trailing bytes.
+            if body_len >= expected_len && body_len < expected_len + FACET_LEN {
+                return Ok(Encoding::Binary);
             }
         }
+
+        // Names may hold UTF-8 (i.e. from localized CAD tools), and the last character read may
+        // have been cut off part way through.
+        let text = match std::str::from_utf8(&leading) {
+            Ok(text) => text,
+            Err(why) if why.error_len().is_none() => std::str::from_utf8(&leading[..why.valid_up_to()]).unwrap(),
+            Err(_) => return Ok(Encoding::Binary)
+        };
+
+        let is_text = |c: char| !c.is_control() || c.is_ascii_whitespace() || c == '\x0b';
+
+        if text.chars().all(is_text) {
+            Ok(Encoding::Ascii)
+        } else {
+            Ok(Encoding::Binary)
+        }
+    }
+
+    /// Writes `facets` as an ascii STL, named `name` (or left unnamed).
+    ///
+    /// The output parses back to exactly the same facets.
+    ///
+    pub fn write_ascii<W: Write>(mut writer: W, facets: &[Facet], name: Option<&str>) -> Result<(), StlError> {
+        writer_ascii::write_ascii_stl(&mut writer, facets, name.unwrap_or(""))
+    }
+
+    /// Writes each of `solids` as a named solid, one after the other, in a single ascii STL.
+    ///
+    pub fn write_ascii_solids<W: Write>(mut writer: W, solids: &[Solid]) -> Result<(), StlError> {
+        for solid in solids {
+            writer_ascii::write_ascii_stl(&mut writer, &solid.facets, &solid.name)?;
+        }
+        Ok(())
+    }
+
+    /// Writes `facets` as a binary STL with the given 80-byte `header` (or a default one).
+    ///
+    /// The output parses back to exactly the same facets.
+    ///
+    pub fn write_binary<W: Write>(mut writer: W, facets: &[Facet], header: Option<&[u8; 80]>) -> Result<(), StlError> {
+        let header = match header {
+            Some(header) => *header,
+            None => {
+                let mut header = [0; 80];
+                header[..DEFAULT_BINARY_HEADER.len()].copy_from_slice(DEFAULT_BINARY_HEADER);
+                header
+            }
+        };
+
+        writer_binary::write_binary_stl(&mut writer, facets, &header)
     }
 }
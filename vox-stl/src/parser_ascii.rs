@@ -1,6 +1,6 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader, Seek};
+use std::io::{BufRead, BufReader, Read};
 
+use crate::error::StlError;
 use crate::fwd::{Facet, Vertex, Normal, Pt3};
 
 /// These are the states of the ascii .stl parser state-machine.
@@ -11,113 +11,214 @@ enum ParsingState {
     ExpectingLoopStart,
     ExpectingLoopEnd,
     ExpectingVertex,
+    ExpectingSolidStartOrEof,
     Done
 }
 
-/// A simple parser for ascii STL files.
+impl ParsingState {
+    /// [returns] the keywords that are accepted in this state.
+    fn expected(&self) -> &'static [&'static str] {
+        match self {
+            ParsingState::ExpectingSolidStart               => &["solid"],
+            ParsingState::ExpectingStartOfFacetOrEndOfSolid => &["facet normal", "endsolid"],
+            ParsingState::ExpectingFacetEnd                 => &["endfacet"],
+            ParsingState::ExpectingLoopStart                => &["outer loop"],
+            ParsingState::ExpectingLoopEnd                  => &["endloop"],
+            ParsingState::ExpectingVertex                   => &["vertex"],
+            ParsingState::ExpectingSolidStartOrEof          => &["solid"],
+            ParsingState::Done                              => &[]
+        }
+    }
+}
+
+/// [returns] the 1-based column at which `token` starts within `line`.
+///
+/// [note]    `token` must be a sub-slice of `line`.
+fn column_of(line: &str, token: &str) -> usize {
+    token.as_ptr() as usize - line.as_ptr() as usize + 1
+}
+
+/// [returns] the three floats following the keyword(s) in `parts`.
+fn parse_xyz(line: &str, line_no: usize, parts: &[&str]) -> Result<[f32; 3], StlError> {
+    let mut xyz = [0.0; 3];
+
+    for (value, token) in xyz.iter_mut().zip(&parts[parts.len() - 3..]) {
+        *value = token.parse::<f32>().map_err(|_| StlError::BadNumber {
+            line: line_no,
+            column: column_of(line, token),
+            token: token.to_string()
+        })?;
+    }
+
+    Ok(xyz)
+}
+
+/// A simple streaming parser for ascii STL files, yielding one facet at a time.
+///
+/// Files may hold several (named) solids one after another, as CAD assemblies are often exported
+/// that way. The facets of all of them are yielded in order. The reader is expected to be
+/// positioned at the start of the STL data. After an error has been yielded the iterator is
+/// exhausted.
 ///
 /// see: https://en.wikipedia.org/wiki/STL_(file_format)#ASCII_STL
 ///
-pub fn facets_from_ascii_stl(file: &mut File) -> Result<Vec<Facet>, String> {
-    file.rewind().unwrap(); // seek back to start
-
-    let mut state = ParsingState::ExpectingSolidStart;
+pub struct AsciiFacets<R> {
+    reader: BufReader<R>,
+    line: String,
+    line_no: usize,
+    state: ParsingState,
+    current_vertex_i: usize,
+    current_facet: Facet,
+    solid_names: Vec<String>
+}
 
-    let mut current_vertex_i = 0;
-    let mut current_facet = Facet::default();
+impl<R: Read> AsciiFacets<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader: BufReader::new(reader),
+            line: String::new(),
+            line_no: 0,
+            state: ParsingState::ExpectingSolidStart,
+            current_vertex_i: 0,
+            current_facet: Facet::default(),
+            solid_names: vec![]
+        }
+    }
 
-    let mut facets = vec![];
+    /// Advances the state-machine by the current line.
+    ///
+    /// [returns] the facet that the line completed, if any.
+    fn step(&mut self) -> Result<Option<Facet>, StlError> {
+        let raw = self.line.trim();
 
-    for (i, line) in BufReader::new(file).lines().enumerate() {
-        let line =  match line {
-            Ok(l) => l,
-            Err(why) => panic!("{}", why)
+        let unexpected = |state: &ParsingState| StlError::UnexpectedToken {
+            line: self.line_no,
+            token: raw.to_string(),
+            expected: state.expected()
         };
 
-        let raw = line.trim();
+        match self.state {
+            ParsingState::Done => {}
 
-        match state {
-            ParsingState::Done => { break; }
-
-            ParsingState::ExpectingSolidStart => {
+            ParsingState::ExpectingSolidStart | ParsingState::ExpectingSolidStartOrEof => {
+                if raw.is_empty() && matches!(self.state, ParsingState::ExpectingSolidStartOrEof) {
+                    // Blank lines between (or after) solids are harmless.
+                    return Ok(None);
+                }
                 if !raw.starts_with("solid") {
-                    return Err(format!("[{}] unexpected token {}", i, raw));
+                    return Err(unexpected(&self.state));
                 }
-                state = ParsingState::ExpectingStartOfFacetOrEndOfSolid;
+                self.solid_names.push(raw["solid".len()..].trim().to_string());
+                self.state = ParsingState::ExpectingStartOfFacetOrEndOfSolid;
             }
 
             ParsingState::ExpectingStartOfFacetOrEndOfSolid => {
                 if raw.starts_with("facet normal") {
-                    state = ParsingState::ExpectingLoopStart;
-
                     let parts = raw.split_whitespace().collect::<Vec<&str>>();
                     if parts.len() != 5 {
-                        return Err(format!("[{}] expected: 'facet normal {{}} {{}} {{}}, got: {}", i, raw));
+                        return Err(unexpected(&self.state));
                     }
 
-                    current_facet.normal = Normal::from(Pt3(
-                        [
-                            parts[2].parse::<f32>().unwrap(),
-                            parts[3].parse::<f32>().unwrap(),
-                            parts[4].parse::<f32>().unwrap()
-                        ]
-                    ));
+                    self.current_facet.normal = Normal::from(Pt3(parse_xyz(&self.line, self.line_no, &parts)?));
 
+                    self.state = ParsingState::ExpectingLoopStart;
                 } else if raw.starts_with("endsolid") {
-                    state = ParsingState::Done;
+                    self.state = ParsingState::ExpectingSolidStartOrEof;
                 } else {
-                    return Err(format!("[{}] unexpected token {}", i, raw));
+                    return Err(unexpected(&self.state));
                 }
             }
 
             ParsingState::ExpectingFacetEnd => {
                 if !raw.starts_with("endfacet") {
-                    return Err(format!("[{}] unexpected token {}", i, raw));
+                    return Err(unexpected(&self.state));
                 }
-                state = ParsingState::ExpectingStartOfFacetOrEndOfSolid;
+                self.state = ParsingState::ExpectingStartOfFacetOrEndOfSolid;
             }
 
             ParsingState::ExpectingLoopStart => {
                 if !raw.starts_with("outer loop") {
-                    return Err(format!("[{}] unexpected token {}", i, raw));
+                    return Err(unexpected(&self.state));
                 }
-                state = ParsingState::ExpectingVertex;
+                self.state = ParsingState::ExpectingVertex;
             }
 
             ParsingState::ExpectingLoopEnd => {
                 if !raw.starts_with("endloop") {
-                    return Err(format!("[{}] unexpected token {}", i, raw));
+                    return Err(unexpected(&self.state));
                 }
-                state = ParsingState::ExpectingFacetEnd;
+                self.state = ParsingState::ExpectingFacetEnd;
             }
 
             ParsingState::ExpectingVertex => {
                 let parts = raw.split_whitespace().collect::<Vec<&str>>();
 
-                if parts.len() != 4 {
-                    return Err(format!("[{}] expected vertex, got {}", i, raw));
-                } else if parts[0] != "vertex" {
-                    return Err(format!("[{}] expected vertex, got {}", i, raw));
+                if parts.len() != 4 || parts[0] != "vertex" {
+                    return Err(unexpected(&self.state));
                 }
 
-                current_facet.tri[current_vertex_i] = Vertex::from(Pt3(
-                    [
-                        parts[1].parse::<f32>().unwrap(),
-                        parts[2].parse::<f32>().unwrap(),
-                        parts[3].parse::<f32>().unwrap()
-                    ]
-                ));
+                self.current_facet.tri[self.current_vertex_i] = Vertex::from(Pt3(parse_xyz(&self.line, self.line_no, &parts)?));
 
-                current_vertex_i += 1;
+                self.current_vertex_i += 1;
 
-                if current_vertex_i == 3 {
-                    current_vertex_i = 0;
-                    state = ParsingState::ExpectingLoopEnd;
-                    facets.push(current_facet.clone());
+                if self.current_vertex_i == 3 {
+                    self.current_vertex_i = 0;
+                    self.state = ParsingState::ExpectingLoopEnd;
+                    return Ok(Some(self.current_facet));
                 }
             }
         }
+
+        Ok(None)
+    }
+}
+
+impl<R> AsciiFacets<R> {
+    /// [returns] the names of the solids encountered so far.
+    pub fn solid_names(&self) -> &[String] {
+        &self.solid_names
     }
 
-    Ok(facets)
+    /// [returns] the index (into `solid_names`) of the solid currently being parsed.
+    pub fn solid_index(&self) -> usize {
+        self.solid_names.len().saturating_sub(1)
+    }
+}
+
+impl<R: Read> Iterator for AsciiFacets<R> {
+    type Item = Result<Facet, StlError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let ParsingState::Done = self.state {
+                return None;
+            }
+
+            self.line.clear();
+
+            let result = match self.reader.read_line(&mut self.line) {
+                Err(why) => Err(StlError::from(why)),
+                Ok(0) => match self.state {
+                    ParsingState::ExpectingSolidStartOrEof => {
+                        self.state = ParsingState::Done;
+                        return None;
+                    }
+                    _ => Err(StlError::UnexpectedEof { line: self.line_no, expected: self.state.expected() })
+                },
+                Ok(_) => {
+                    self.line_no += 1;
+                    self.step()
+                }
+            };
+
+            match result {
+                Ok(None) => continue,
+                Ok(Some(facet)) => return Some(Ok(facet)),
+                Err(why) => {
+                    self.state = ParsingState::Done;
+                    return Some(Err(why));
+                }
+            }
+        }
+    }
 }
@@ -1,57 +1,155 @@
-use std::fs::File;
-use std::io::{Seek, SeekFrom};
+use std::io::{BufReader, Read, Seek, SeekFrom};
 
+use crate::error::StlError;
 use crate::fwd::{Facet, Pt3};
 
 use byteorder::{ReadBytesExt, LittleEndian};
+use rayon::prelude::*;
 
-/// A simple parser for binary STL files.
+/// The size of the binary header, including the triangle count.
+pub(crate) const HEADER_LEN: u64 = 84;
+
+/// The size of a single triangle record in the binary body.
+pub(crate) const FACET_LEN: u64 = 50;
+
+fn read_pt3<R: Read>(reader: &mut R) -> Result<Pt3, StlError> {
+    Ok(Pt3([
+        reader.read_f32::<LittleEndian>()?,
+        reader.read_f32::<LittleEndian>()?,
+        reader.read_f32::<LittleEndian>()?,
+    ]))
+}
+
+/// Checks the triangle count from the header against the length of the body.
+fn check_body_len(num_triangles: u32, body_len: u64) -> Result<(), StlError> {
+    let expected_len = num_triangles as u64 * FACET_LEN;
+
+    if body_len < expected_len {
+        Err(StlError::TruncatedBinary {
+            expected: num_triangles,
+            available: (body_len / FACET_LEN) as u32
+        })
+    } else if body_len >= expected_len + FACET_LEN {
+        Err(StlError::TriangleCountMismatch { header: num_triangles, body: body_len })
+    } else {
+        Ok(())
+    }
+}
+
+fn read_facet<R: Read>(reader: &mut R) -> Result<Facet, StlError> {
+    let normal = read_pt3(reader)?;
+
+    let tri = [
+        read_pt3(reader)?,
+        read_pt3(reader)?,
+        read_pt3(reader)?
+    ];
+
+    let attribute = reader.read_u16::<LittleEndian>()?;
+
+    Ok(Facet{ tri, normal, attribute })
+}
+
+/// A simple streaming parser for binary STL files, yielding one facet at a time.
+///
+/// The triangle count in the header is checked against the length of the body before anything
+/// is read, so a truncated or corrupt file is reported rather than partially parsed. The reader is
+/// expected to be positioned at the start of the STL data. After an error has been yielded the
+/// iterator is exhausted.
 ///
 /// see: https://en.wikipedia.org/wiki/STL_(file_format)#Binary_STL
 ///
-pub fn facets_from_binary_stl(file: &mut File) -> Result<Vec<Facet>, String> {
-    file.seek(SeekFrom::Start(80)).unwrap();
-
-    let num_triangles = file.read_u32::<LittleEndian>().unwrap();
-
-    let mut facets = vec![];
-
-    for _ in 0..num_triangles {
-        let normal = Pt3(
-            [
-                file.read_f32::<LittleEndian>().unwrap(),
-                file.read_f32::<LittleEndian>().unwrap(),
-                file.read_f32::<LittleEndian>().unwrap(),
-            ]
-        );
-
-        let tri = [
-            Pt3([
-                file.read_f32::<LittleEndian>().unwrap(),
-                file.read_f32::<LittleEndian>().unwrap(),
-                file.read_f32::<LittleEndian>().unwrap(),
-            ]),
-            Pt3([
-                file.read_f32::<LittleEndian>().unwrap(),
-                file.read_f32::<LittleEndian>().unwrap(),
-                file.read_f32::<LittleEndian>().unwrap(),
-            ]),
-            Pt3([
-                file.read_f32::<LittleEndian>().unwrap(),
-                file.read_f32::<LittleEndian>().unwrap(),
-                file.read_f32::<LittleEndian>().unwrap(),
-            ])
-        ];
-
-        facets.push(
-            Facet{
-                tri,
-                normal
+pub struct BinaryFacets<R> {
+    reader: BufReader<R>,
+    header: [u8; 80],
+    remaining: u32
+}
+
+impl<R: Read + Seek> BinaryFacets<R> {
+    pub fn new(mut reader: R) -> Result<Self, StlError> {
+        let start = reader.stream_position()?;
+
+        let mut header = [0; 80];
+        reader.read_exact(&mut header)?;
+
+        let num_triangles = reader.read_u32::<LittleEndian>()?;
+
+        let body_len = reader.seek(SeekFrom::End(0))?.saturating_sub(start + HEADER_LEN);
+        check_body_len(num_triangles, body_len)?;
+
+        reader.seek(SeekFrom::Start(start + HEADER_LEN))?;
+
+        // Each triangle is a handful of small reads, so we buffer them.
+        Ok(Self {
+            reader: BufReader::new(reader),
+            header,
+            remaining: num_triangles
+        })
+    }
+}
+
+impl<R> BinaryFacets<R> {
+    pub fn header(&self) -> &[u8; 80] {
+        &self.header
+    }
+}
+
+impl<R: Read> Iterator for BinaryFacets<R> {
+    type Item = Result<Facet, StlError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        match read_facet(&mut self.reader) {
+            Ok(facet) => {
+                self.remaining -= 1;
+                Some(Ok(facet))
+            }
+            Err(why) => {
+                self.remaining = 0;
+                Some(Err(why))
             }
-        );
+        }
+    }
 
-        file.seek(SeekFrom::Current(2)).unwrap();
+    // A truncated file may end after any facet, so at least one more is all that can be promised.
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining.min(1) as usize, Some(self.remaining as usize))
     }
+}
+
+/// Decodes a single 50-byte triangle record.
+fn decode_facet(record: &[u8]) -> Facet {
+    let f = |i: usize| f32::from_le_bytes([record[i], record[i + 1], record[i + 2], record[i + 3]]);
+    let pt = |i: usize| Pt3([f(i), f(i + 4), f(i + 8)]);
+
+    Facet {
+        normal: pt(0),
+        tri: [pt(12), pt(24), pt(36)],
+        attribute: u16::from_le_bytes([record[48], record[49]])
+    }
+}
+
+/// Decodes a whole binary STL that is already in memory (or memory-mapped).
+///
+/// Since the body is made of fixed-size records, they are decoded in parallel.
+///
+pub fn facets_from_binary_bytes(bytes: &[u8]) -> Result<Vec<Facet>, StlError> {
+    if bytes.len() < HEADER_LEN as usize {
+        return Err(StlError::from(std::io::Error::from(std::io::ErrorKind::UnexpectedEof)));
+    }
+
+    let num_triangles = u32::from_le_bytes([bytes[80], bytes[81], bytes[82], bytes[83]]);
+
+    let body = &bytes[HEADER_LEN as usize..];
+    check_body_len(num_triangles, body.len() as u64)?;
 
-    Ok(facets)
+    Ok(
+        body[..num_triangles as usize * FACET_LEN as usize]
+            .par_chunks_exact(FACET_LEN as usize)
+            .map(decode_facet)
+            .collect()
+    )
 }
@@ -0,0 +1,100 @@
+use std::fs::File;
+use std::io::{Read, Seek};
+use std::path::Path;
+
+use crate::error::StlError;
+use crate::fwd::Facet;
+use crate::parser_ascii::AsciiFacets;
+use crate::parser_binary::BinaryFacets;
+use crate::stl::{detect_encoding, Encoding};
+
+enum Parser<R> {
+    Ascii(AsciiFacets<R>),
+    Binary(BinaryFacets<R>)
+}
+
+/// Streams the facets of an STL file one at a time, so that files far larger than memory can be
+/// processed.
+///
+pub struct FacetReader<R> {
+    parser: Parser<R>
+}
+
+impl FacetReader<File> {
+    pub fn open(path: &Path) -> Result<Self, StlError> {
+        Self::new(File::open(path)?)
+    }
+}
+
+impl<R: Read + Seek> FacetReader<R> {
+    /// Reads the STL data that starts at the current position of `reader`.
+    ///
+    pub fn new(mut reader: R) -> Result<Self, StlError> {
+        let encoding = detect_encoding(&mut reader)?;
+        Self::with_encoding(reader, encoding)
+    }
+
+    /// Like `new`, but skips detection and reads the data as `encoding`.
+    ///
+    pub fn with_encoding(reader: R, encoding: Encoding) -> Result<Self, StlError> {
+        let parser = match encoding {
+            Encoding::Ascii =>  { Parser::Ascii(AsciiFacets::new(reader)) }
+            Encoding::Binary => { Parser::Binary(BinaryFacets::new(reader)?) }
+        };
+
+        Ok(Self { parser })
+    }
+}
+
+impl<R> FacetReader<R> {
+    pub fn encoding(&self) -> Encoding {
+        match self.parser {
+            Parser::Ascii(_) => Encoding::Ascii,
+            Parser::Binary(_) => Encoding::Binary
+        }
+    }
+
+    /// [returns] the names of the solids encountered so far (a binary file has a single unnamed one).
+    pub fn solid_names(&self) -> &[String] {
+        static UNNAMED: [String; 1] = [String::new()];
+
+        match &self.parser {
+            Parser::Ascii(facets) => facets.solid_names(),
+            Parser::Binary(_) => &UNNAMED
+        }
+    }
+
+    /// [returns] the index (into `solid_names`) of the solid that the last facet belonged to.
+    pub fn solid_index(&self) -> usize {
+        match &self.parser {
+            Parser::Ascii(facets) => facets.solid_index(),
+            Parser::Binary(_) => 0
+        }
+    }
+
+    /// [returns] the 80-byte header of a binary file, or None for an ascii file.
+    pub fn header(&self) -> Option<&[u8; 80]> {
+        match &self.parser {
+            Parser::Ascii(_) => None,
+            Parser::Binary(facets) => Some(facets.header())
+        }
+    }
+}
+
+impl<R: Read> Iterator for FacetReader<R> {
+    type Item = Result<Facet, StlError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.parser {
+            Parser::Ascii(facets) => facets.next(),
+            Parser::Binary(facets) => facets.next()
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match &self.parser {
+            Parser::Ascii(facets) => facets.size_hint(),
+            Parser::Binary(facets) => facets.size_hint()
+        }
+    }
+}
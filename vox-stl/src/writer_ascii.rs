@@ -0,0 +1,38 @@
+use std::io::{BufWriter, Write};
+
+use crate::error::StlError;
+use crate::fwd::{Facet, Pt3};
+
+fn write_xyz<W: Write>(writer: &mut W, keyword: &str, p: &Pt3) -> Result<(), StlError> {
+    // `{:e}` prints the shortest representation that parses back to the same f32, which is what
+    // makes the output round-trip exactly.
+    writeln!(writer, "{} {:e} {:e} {:e}", keyword, p.0[0], p.0[1], p.0[2])?;
+    Ok(())
+}
+
+/// A simple writer for ascii STL files.
+///
+/// The `name` is written after both 'solid' and 'endsolid', and so should be a single line.
+///
+/// see: https://en.wikipedia.org/wiki/STL_(file_format)#ASCII_STL
+///
+pub fn write_ascii_stl<W: Write>(writer: &mut W, facets: &[Facet], name: &str) -> Result<(), StlError> {
+    let mut writer = BufWriter::new(writer);
+
+    writeln!(writer, "solid {}", name)?;
+
+    for facet in facets {
+        write_xyz(&mut writer, "  facet normal", &facet.normal)?;
+        writeln!(writer, "    outer loop")?;
+        for vertex in &facet.tri {
+            write_xyz(&mut writer, "      vertex", vertex)?;
+        }
+        writeln!(writer, "    endloop")?;
+        writeln!(writer, "  endfacet")?;
+    }
+
+    writeln!(writer, "endsolid {}", name)?;
+
+    writer.flush()?;
+    Ok(())
+}
@@ -0,0 +1,40 @@
+use std::io::{self, BufWriter, Write};
+
+use crate::error::StlError;
+use crate::fwd::{Facet, Pt3};
+
+use byteorder::{WriteBytesExt, LittleEndian};
+
+fn write_pt3<W: Write>(writer: &mut W, p: &Pt3) -> Result<(), StlError> {
+    for v in p.0 {
+        writer.write_f32::<LittleEndian>(v)?;
+    }
+    Ok(())
+}
+
+/// A simple writer for binary STL files.
+///
+/// see: https://en.wikipedia.org/wiki/STL_(file_format)#Binary_STL
+///
+pub fn write_binary_stl<W: Write>(writer: &mut W, facets: &[Facet], header: &[u8; 80]) -> Result<(), StlError> {
+    let num_triangles = u32::try_from(facets.len()).map_err(|_| {
+        io::Error::new(io::ErrorKind::InvalidInput, "binary STL files hold at most u32::MAX triangles")
+    })?;
+
+    let mut writer = BufWriter::new(writer);
+
+    writer.write_all(header)?;
+    writer.write_u32::<LittleEndian>(num_triangles)?;
+
+    for facet in facets {
+        write_pt3(&mut writer, &facet.normal)?;
+        for vertex in &facet.tri {
+            write_pt3(&mut writer, vertex)?;
+        }
+
+        writer.write_u16::<LittleEndian>(facet.attribute)?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
@@ -20,3 +20,301 @@ fn parse_binary_stl() {
     let facets = stl::parse_from_file(Path::new("../resources/models/binary-cube.stl")).unwrap();
     assert_eq!(facets.len(), 12);
 }
+
+#[test]
+fn parse_missing_file() {
+    let result = stl::parse_from_file(Path::new("../resources/models/does-not-exist.stl"));
+    assert!(matches!(result, Err(stl::StlError::Io(_))));
+}
+
+#[test]
+fn parse_malformed_ascii_stl() {
+    // [Scenario] a coordinate that isn't a number
+    {
+        let src = "solid s\n  facet normal 0 0 1\n    outer loop\n      vertex 0 0 0\n      vertex 1 zero 0\n";
+        match stl::parse_from_bytes(src.as_bytes()) {
+            Err(stl::StlError::BadNumber { line, column, token }) => {
+                assert_eq!(line, 5);
+                assert_eq!(column, 16);
+                assert_eq!(token, "zero");
+            }
+            other => panic!("expected BadNumber, got {:?}", other)
+        }
+    }
+
+    // [Scenario] a keyword out of place
+    {
+        let src = "solid s\n  facet normal 0 0 1\n    endloop\n";
+        match stl::parse_from_bytes(src.as_bytes()) {
+            Err(stl::StlError::UnexpectedToken { line, token, expected }) => {
+                assert_eq!(line, 3);
+                assert_eq!(token, "endloop");
+                assert_eq!(expected, &["outer loop"]);
+            }
+            other => panic!("expected UnexpectedToken, got {:?}", other)
+        }
+    }
+
+    // [Scenario] the file ends in the middle of a facet
+    {
+        let src = "solid s\n  facet normal 0 0 1\n    outer loop\n      vertex 0 0 0\n";
+        match stl::parse_from_bytes(src.as_bytes()) {
+            Err(stl::StlError::UnexpectedEof { line, expected }) => {
+                assert_eq!(line, 4);
+                assert_eq!(expected, &["vertex"]);
+            }
+            other => panic!("expected UnexpectedEof, got {:?}", other)
+        }
+    }
+}
+
+#[test]
+fn parse_malformed_binary_stl() {
+    let cube = std::fs::read("../resources/models/binary-cube.stl").unwrap();
+
+    // [Scenario] the body is cut off in the middle of a triangle
+    match stl::parse_from_bytes(&cube[..cube.len() - 30]) {
+        Err(stl::StlError::TruncatedBinary { expected, available }) => {
+            assert_eq!(expected, 12);
+            assert_eq!(available, 11);
+        }
+        other => panic!("expected TruncatedBinary, got {:?}", other)
+    }
+
+    // [Scenario] the header declares fewer triangles than the body holds
+    {
+        let mut bytes = cube.clone();
+        bytes[80..84].copy_from_slice(&10u32.to_le_bytes());
+        match stl::parse_from_bytes(&bytes) {
+            Err(stl::StlError::TriangleCountMismatch { header, body }) => {
+                assert_eq!(header, 10);
+                assert_eq!(body, 601);
+            }
+            other => panic!("expected TriangleCountMismatch, got {:?}", other)
+        }
+    }
+
+    // [Scenario] the file is too short to hold a header
+    assert!(matches!(stl::parse_from_bytes(&cube[..40]), Err(stl::StlError::Io(_))));
+}
+
+#[test]
+fn parse_from_memory() {
+    for name in ["ascii-cube.stl", "ascii-sphere.stl", "binary-cube.stl"] {
+        let path = Path::new("../resources/models").join(name);
+        let bytes = std::fs::read(&path).unwrap();
+
+        let from_file = stl::parse_from_file(&path).unwrap();
+        assert_eq!(stl::parse_from_bytes(&bytes).unwrap(), from_file);
+
+        // [Scenario] the STL data is embedded part-way through a larger stream
+        let mut embedded = b"some other data".to_vec();
+        embedded.extend_from_slice(&bytes);
+
+        let mut cursor = std::io::Cursor::new(embedded);
+        cursor.set_position(15);
+        assert_eq!(stl::parse_from_reader(cursor).unwrap(), from_file);
+    }
+}
+
+#[test]
+fn detect_binary_stl_with_solid_header() {
+    let cube = std::fs::read("../resources/models/binary-cube.stl").unwrap();
+
+    let mut bytes = cube.clone();
+    bytes[..80].fill(b' ');
+    bytes[..22].copy_from_slice(b"solid exported by CAD ");
+
+    assert_eq!(stl::detect_encoding(&mut std::io::Cursor::new(&bytes)).unwrap(), stl::Encoding::Binary);
+    assert_eq!(stl::parse_from_bytes(&bytes).unwrap(), stl::parse_from_bytes(&cube).unwrap());
+
+    // [Scenario] the triangle count is off, but the body clearly isn't text
+    let mut truncated = bytes.clone();
+    truncated.truncate(bytes.len() - 50);
+    assert!(matches!(stl::parse_from_bytes(&truncated), Err(stl::StlError::TruncatedBinary { .. })));
+
+    // [Scenario] a genuine ascii file
+    let ascii = std::fs::read("../resources/models/ascii-sphere.stl").unwrap();
+    assert_eq!(stl::detect_encoding(&mut std::io::Cursor::new(&ascii)).unwrap(), stl::Encoding::Ascii);
+
+    // [Scenario] an ascii file whose solid is named in UTF-8, including with a character cut off
+    //            by the end of the bytes that are sniffed
+    let body = &ascii[ascii.iter().position(|&b| b == b'\n').unwrap()..];
+    for name in ["Müller", &format!("{}ü", "x".repeat(511 - "solid ".len()))] {
+        let named = [format!("solid {}", name).as_bytes(), body].concat();
+        assert_eq!(stl::detect_encoding(&mut std::io::Cursor::new(&named)).unwrap(), stl::Encoding::Ascii);
+
+        let solids = stl::parse_solids_from_bytes(&named).unwrap();
+        assert_eq!(solids[0].name, name);
+        assert_eq!(solids[0].facets, stl::parse_from_bytes(&ascii).unwrap());
+    }
+}
+
+#[test]
+fn parse_with_forced_encoding() {
+    let path = Path::new("../resources/models/binary-cube.stl");
+
+    let facets = stl::parse_from_file_with_encoding(path, stl::Encoding::Binary).unwrap();
+    assert_eq!(facets.len(), 12);
+
+    // The binary body isn't valid UTF-8, so reading it as text fails.
+    let result = stl::parse_from_file_with_encoding(path, stl::Encoding::Ascii);
+    assert!(matches!(result, Err(stl::StlError::Io(_))));
+}
+
+#[test]
+fn write_round_trip() {
+    for entry in std::fs::read_dir("../resources/models").unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().and_then(|e| e.to_str()) != Some("stl") {
+            continue;
+        }
+
+        let facets = stl::parse_from_file(&path).unwrap();
+
+        // [Scenario] ascii
+        {
+            let mut bytes = vec![];
+            stl::write_ascii(&mut bytes, &facets, Some("round trip")).unwrap();
+            assert_eq!(stl::detect_encoding(&mut std::io::Cursor::new(&bytes)).unwrap(), stl::Encoding::Ascii);
+            assert_eq!(stl::parse_from_bytes(&bytes).unwrap(), facets, "{:?}", path);
+        }
+
+        // [Scenario] binary, with a header that starts with 'solid'
+        {
+            let mut header = [b' '; 80];
+            header[..5].copy_from_slice(b"solid");
+
+            let mut bytes = vec![];
+            stl::write_binary(&mut bytes, &facets, Some(&header)).unwrap();
+            assert_eq!(bytes.len(), 84 + 50 * facets.len());
+
+            let reparsed = stl::parse_from_bytes(&bytes).unwrap();
+            assert_eq!(reparsed, facets, "{:?}", path);
+
+            // Writing the re-parsed facets gives back the very same bytes
+            let mut rewritten = vec![];
+            stl::write_binary(&mut rewritten, &reparsed, Some(&header)).unwrap();
+            assert_eq!(rewritten, bytes);
+        }
+    }
+}
+
+#[test]
+fn stream_facets() {
+    for name in ["ascii-sphere.stl", "binary-cube.stl"] {
+        let path = Path::new("../resources/models").join(name);
+
+        let reader = stl::FacetReader::open(&path).unwrap();
+        let streamed : Vec<_> = reader.map(Result::unwrap).collect();
+        assert_eq!(streamed, stl::parse_from_file(&path).unwrap());
+    }
+
+    // [Scenario] a binary file promises no more than its triangle count, and no fewer than the
+    //            next facet (or error)
+    let mut reader = stl::FacetReader::open(Path::new("../resources/models/binary-cube.stl")).unwrap();
+    assert_eq!(reader.size_hint(), (1, Some(12)));
+    reader.by_ref().for_each(drop);
+    assert_eq!(reader.size_hint(), (0, Some(0)));
+
+    // [Scenario] the iterator stops after the first error
+    let src = "solid s\n  facet normal 0 0 1\n    outer loop\n      vertex 0 0 nope\n";
+    let mut reader = stl::FacetReader::new(std::io::Cursor::new(src)).unwrap();
+    assert_eq!(reader.encoding(), stl::Encoding::Ascii);
+    assert!(matches!(reader.next(), Some(Err(stl::StlError::BadNumber { line: 4, column: 18, .. }))));
+    assert!(reader.next().is_none());
+}
+
+#[test]
+fn preserve_binary_header_and_attributes() {
+    use vox_stl::color::{ColorConvention, HeaderColors};
+
+    let mut facets = stl::parse_from_file(Path::new("../resources/models/binary-cube.stl")).unwrap();
+    for (i, facet) in facets.iter_mut().enumerate() {
+        facet.attribute = ColorConvention::Magics.encode([(i * 20) as u8, 128, 255]);
+    }
+    facets[0].attribute = 0x8000; // use the object colour
+
+    let mut header = [0; 80];
+    header[..10].copy_from_slice(b"COLOR=\xff\x00\x00\xff");
+
+    let mut bytes = vec![];
+    stl::write_binary(&mut bytes, &facets, Some(&header)).unwrap();
+
+    let reader = stl::FacetReader::new(std::io::Cursor::new(&bytes)).unwrap();
+    assert_eq!(reader.header(), Some(&header));
+    assert_eq!(ColorConvention::detect(&header), ColorConvention::Magics);
+    assert_eq!(HeaderColors::from_header(&header).color, Some([255, 0, 0, 255]));
+    assert_eq!(HeaderColors::from_header(&header).material, None);
+
+    let reparsed : Vec<_> = reader.map(Result::unwrap).collect();
+    assert_eq!(reparsed, facets);
+
+    assert_eq!(ColorConvention::Magics.decode(reparsed[0].attribute), None);
+    assert_eq!(ColorConvention::Magics.decode(reparsed[1].attribute), Some([16, 132, 255]));
+
+    // [Scenario] VisCAM flags valid colours with the top bit
+    let viscam = ColorConvention::VisCam.encode([255, 0, 8]);
+    assert_eq!(viscam, 0x8000 | (31 << 10) | 1);
+    assert_eq!(ColorConvention::VisCam.decode(viscam), Some([255, 0, 8]));
+    assert_eq!(ColorConvention::VisCam.decode(0), None);
+
+    // [Scenario] ascii files have neither
+    let reader = stl::FacetReader::open(Path::new("../resources/models/ascii-cube.stl")).unwrap();
+    assert_eq!(reader.header(), None);
+    assert!(reader.map(Result::unwrap).all(|f| f.attribute == 0));
+}
+
+#[test]
+fn parse_multiple_solids() {
+    use vox_stl::fwd::Solid;
+
+    let cube = stl::parse_from_file(Path::new("../resources/models/ascii-cube.stl")).unwrap();
+    let sphere = stl::parse_from_file(Path::new("../resources/models/ascii-sphere.stl")).unwrap();
+
+    let solids = vec![
+        Solid { name: "cube".to_string(), facets: cube.clone() },
+        Solid { name: "empty part".to_string(), facets: vec![] },
+        Solid { name: "sphere".to_string(), facets: sphere.clone() },
+    ];
+
+    let mut bytes = vec![];
+    stl::write_ascii_solids(&mut bytes, &solids).unwrap();
+    bytes.extend_from_slice(b"\n\n");
+
+    assert_eq!(stl::parse_solids_from_bytes(&bytes).unwrap(), solids);
+    assert_eq!(stl::parse_from_bytes(&bytes).unwrap().len(), cube.len() + sphere.len());
+
+    // [Scenario] single solid files keep their name
+    let solids = stl::parse_solids_from_file(Path::new("../resources/models/ascii-cube.stl")).unwrap();
+    assert_eq!(solids.len(), 1);
+    assert_eq!(solids[0].name, "MYSOLID");
+
+    let solids = stl::parse_solids_from_file(Path::new("../resources/models/binary-cube.stl")).unwrap();
+    assert_eq!(solids.len(), 1);
+    assert_eq!(solids[0].name, "");
+    assert_eq!(solids[0].facets.len(), 12);
+
+    // [Scenario] something other than a solid after the first one
+    let src = "solid a\nendsolid a\nfacet normal 0 0 1\n";
+    assert!(matches!(stl::parse_solids_from_bytes(src.as_bytes()), Err(stl::StlError::UnexpectedToken { line: 3, .. })));
+}
+
+#[test]
+fn parse_binary_in_parallel() {
+    let path = Path::new("../resources/models/binary-cube.stl");
+
+    let streamed : Vec<_> = stl::FacetReader::open(path).unwrap().map(Result::unwrap).collect();
+    assert_eq!(stl::parse_from_file(path).unwrap(), streamed);
+
+    // The bulk path applies the same checks as the streaming one.
+    let cube = std::fs::read(path).unwrap();
+    assert!(matches!(stl::parse_from_bytes(&cube[..cube.len() - 30]), Err(stl::StlError::TruncatedBinary { .. })));
+    assert!(matches!(stl::parse_from_bytes(&cube[..83]), Err(stl::StlError::Io(_))));
+
+    let empty = std::env::temp_dir().join(format!("vox-stl-{}-empty.stl", std::process::id()));
+    std::fs::write(&empty, b"").unwrap();
+    let result = stl::parse_from_file(&empty);
+    std::fs::remove_file(&empty).unwrap();
+    assert!(matches!(result, Err(stl::StlError::Io(_))));
+}
@@ -1,12 +1,10 @@
 use std::path::Path;
 use std::sync::Arc;
-use image::Rgb;
 
 use nalgebra as na;
 use nalgebra::{Isometry3, Translation3, Vector3};
 use nalgebra_glm::Vec3;
-use rand::{random, thread_rng};
-use rand::distributions::Distribution;
+use rand::random;
 
 use vox_graphics::{
     texture::MatcapTexture,
@@ -15,6 +13,8 @@ use vox_graphics::{
     surface::Surface,
     model::Model,
     model::Mesh,
+    normals::NormalWeighting,
+    scene::{Node, Scene},
 };
 
 mod debug_utils;
@@ -23,25 +23,6 @@ use crate::debug_utils::random_col;
 
 use minifb::{Key, Window, WindowOptions, ScaleMode, KeyRepeat};
 
-fn move_models(models: &mut Vec<(Model, Rgb<u8>)>){
-    let between = rand::distributions::Uniform::from(-1.0..1.0);
-    let mut rng = thread_rng();
-
-    for (model, _) in models {
-        if random::<f32>() < 0.5 {
-            model.transform.append_translation_mut(
-                &Translation3::from(
-                    Vector3::new(
-                        between.sample(&mut rng),
-                        between.sample(&mut rng),
-                        between.sample(&mut rng)
-                    )
-                )
-            );
-        }
-    }
-}
-
 fn main() {
 
     let window_w = 600usize;
@@ -88,21 +69,47 @@ fn main() {
     let matcap1 = Arc::new(MatcapTexture::from_file(&Path::new("resources/matcaps/normal_256.png")));
     // let matcap2 = Arc::new(MatcapTexture::from_file(&Path::new("resources/matcaps/gold_matcap.png")));
 
-    let mesh_path = Path::new("/Users/matthewnielsen/Downloads/3Dbenchy.stl");
-    let mesh = Arc::new(
-        Mesh::from_facets(
-            vox_stl::stl::parse_from_file(mesh_path).unwrap()
-        )
-    );
-
-    // let sphere = Arc::new(Mesh::from_facets(vox_stl::stl::parse_from_file(Path::new("/Users/matthewnielsen/Downloads/5k_sphere.STL")).unwrap()));
+    let model_path = std::env::args().nth(1).unwrap_or("/Users/matthewnielsen/Downloads/3Dbenchy.stl".to_string());
+    let model_path = Path::new(&model_path);
 
-    let mut model1 = Model {
-        mesh: mesh.clone(),
-        transform: na::convert(Isometry3::translation(0.0,0.0,0.0)),
-        texture: matcap1.clone()
+    // glTF and 3MF files hold whole scenes, anything else is taken to be a single mesh.
+    let models = match model_path.extension().and_then(|e| e.to_str()) {
+        Some("gltf" | "glb") => {
+            Model::from_gltf(&vox_gltf::gltf::parse_from_file(model_path).unwrap(), matcap1.clone())
+        }
+        Some("3mf") => {
+            Model::from_3mf(&vox_3mf::three_mf::parse_from_file(model_path).unwrap(), matcap1.clone())
+        }
+        extension => {
+            let mut mesh = match extension {
+                Some("off") => Mesh::from_off(&vox_off::off::parse_from_file(model_path).unwrap()),
+                _ => Mesh::try_from_stl(vox_stl::stl::FacetReader::open(model_path).unwrap()).unwrap()
+            };
+
+            // Smooth over anything shallower than 30 degrees, keeping sharper edges hard.
+            if mesh.normals.is_empty() {
+                mesh.compute_normals(30f32.to_radians(), NormalWeighting::Angle);
+            }
+            let mesh = Arc::new(mesh);
+
+            vec![Model {
+                mesh: mesh.clone(),
+                transform: na::convert(Isometry3::translation(0.0,0.0,0.0)),
+                texture: matcap1.clone(),
+                base_color: None,
+                lods: vec![]
+            }]
+        }
     };
 
+    // Each model gets a node of its own, at the root of the scene.
+    let mut scene = Scene::new();
+    for (i, model) in models.into_iter().enumerate() {
+        scene.add(None, Node { model: Some(model), ..Node::new(&format!("model {}", i)) });
+    }
+
+    // let sphere = Arc::new(Mesh::from_facets(vox_stl::stl::parse_from_file(Path::new("/Users/matthewnielsen/Downloads/5k_sphere.STL")).unwrap()));
+
     while window.is_open() && !window.is_key_down(Key::Escape) {
         let mut needsRedraw = false;
 
@@ -167,13 +174,13 @@ fn main() {
                 500.0
             );
 
-            grr::render_model(&model1, &camera, &p, &mut preview_frame);
+            grr::render_scene_from(&scene, &camera, &p, &mut preview_frame);
             preview_frame.to_img().save("/tmp/preview.png").expect("failed to save image");
         }
 
         if needsRedraw {
             surface.clear();
-            grr::render_model(&model1, &camera, &proj, &mut surface);
+            grr::render_scene_from(&scene, &camera, &proj, &mut surface);
             // grr::render_model(&model2, &camera, &proj, &mut surface);
             surface.fill_buffer(&mut buffer);
         }